@@ -0,0 +1,96 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+
+use crate::{Position, PositionRange, Result, Token, Tokenizer};
+
+/// Erlang source code text and its tokens.
+///
+/// This owns the source text and lazily tokenizes it on demand.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::TokenizedDocument;
+///
+/// let doc = TokenizedDocument::new(String::from("io:format(\"Hello\")."));
+/// assert_eq!(doc.token_at(4).map(|t| t.text().to_owned()), Some("format".to_owned()));
+/// assert_eq!(doc.iter().count(), 7);
+/// ```
+#[derive(Debug)]
+pub struct TokenizedDocument {
+    text: String,
+    cursor: RefCell<Cursor>,
+}
+impl TokenizedDocument {
+    /// Makes a new `TokenizedDocument` instance.
+    pub fn new(text: String) -> Self {
+        TokenizedDocument {
+            text,
+            cursor: RefCell::new(Cursor {
+                tokens: Vec::new(),
+                next_pos: Position::new(),
+            }),
+        }
+    }
+
+    /// Returns the source text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Finishes using this document and returns the source text.
+    pub fn into_text(self) -> String {
+        self.text
+    }
+
+    /// Returns the token containing the byte at `offset`.
+    ///
+    /// The tokens scanned so far are cached, so the text is tokenized only once.
+    /// If `offset` is out of range or a tokenization error occurs before reaching `offset`,
+    /// this method returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::TokenizedDocument;
+    ///
+    /// let doc = TokenizedDocument::new(String::from("foo(Bar)"));
+    /// assert_eq!(doc.token_at(0).map(|t| t.text().to_owned()), Some("foo".to_owned()));
+    /// assert_eq!(doc.token_at(6).map(|t| t.text().to_owned()), Some("Bar".to_owned()));
+    /// assert!(doc.token_at(8).is_none());
+    /// ```
+    pub fn token_at(&self, offset: usize) -> Option<Token> {
+        if offset >= self.text.len() {
+            return None;
+        }
+
+        let mut cursor = self.cursor.borrow_mut();
+        while cursor.next_pos.offset() <= offset {
+            let pos = cursor.next_pos.clone();
+            let token = Token::from_text(&self.text[pos.offset()..], pos).ok()?;
+            cursor.next_pos = token.end_position();
+            cursor.tokens.push(token);
+        }
+        let i = cursor
+            .tokens
+            .partition_point(|t| t.start_position().offset() <= offset);
+        cursor.tokens.get(i.checked_sub(1)?).cloned()
+    }
+
+    /// Returns an iterator over the tokens of the source text.
+    pub fn iter(&self) -> impl Iterator<Item = Result<Token>> + '_ {
+        Tokenizer::new(self.text.as_str())
+    }
+}
+impl From<String> for TokenizedDocument {
+    fn from(f: String) -> Self {
+        TokenizedDocument::new(f)
+    }
+}
+
+#[derive(Debug)]
+struct Cursor {
+    tokens: Vec<Token>,
+    next_pos: Position,
+}
@@ -1,7 +1,15 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::values::Symbol;
 use crate::Position;
 
 /// Possible errors.
-#[derive(Debug, Clone, thiserror::Error)]
+///
+/// An error is serialized as an externally tagged enum
+/// (e.g., `{"UnknownKeyword": {"position": {...}, "keyword": "foo"}}` in JSON).
+/// Note that new variants may be added in the future.
+#[derive(Debug, Clone, thiserror::Error, serde::Serialize, serde::Deserialize)]
 #[non_exhaustive]
 #[allow(missing_docs)]
 pub enum Error {
@@ -22,6 +30,9 @@ pub enum Error {
     MissingToken { position: Position },
 
     /// Unknown keyword.
+    ///
+    /// This is returned only by APIs that demand a keyword (e.g., `KeywordToken::from_text`).
+    /// `Tokenizer` and `Token::from_text` treat non-keyword words as atoms instead.
     #[error("unknown keyword {keyword:?} ({position})")]
     UnknownKeyword { position: Position, keyword: String },
 
@@ -64,6 +75,42 @@ pub enum Error {
     /// Invalid whitespace token.
     #[error("cannot parse a whitespace token ({position})")]
     InvalidWhitespaceToken { position: Position },
+
+    /// Radix of a based integer is out of range.
+    #[error("radix {radix} is out of range 2..=36 ({position})")]
+    InvalidRadix { position: Position, radix: u32 },
+
+    /// Invalid control character.
+    #[error("invalid control character {ch:?} ({position})")]
+    InvalidControlChar { position: Position, ch: char },
+
+    /// Unexpected character (e.g., a stray backtick) which does not start any token.
+    #[error("unexpected character {ch:?} ({position})")]
+    UnexpectedChar { position: Position, ch: char },
+
+    /// A digit is expected (e.g., before or after the decimal point of a float).
+    #[error("expected a digit ({position})")]
+    ExpectedDigit { position: Position },
+
+    /// Invalid UTF-8 sequence.
+    #[error("invalid UTF-8 sequence ({position})")]
+    InvalidUtf8 { position: Position },
+
+    /// String containing a raw newline.
+    ///
+    /// This is returned only if `Tokenizer::forbid_multiline_strings` is enabled.
+    #[error("string contains a raw newline ({position})")]
+    MultilineString { position: Position },
+
+    /// Token exceeds the maximum length.
+    #[error("token is longer than {limit} bytes ({position})")]
+    TokenTooLong { position: Position, limit: usize },
+
+    /// Unbalanced bracket.
+    ///
+    /// `found` is an unmatched closing bracket, or an unclosed opening bracket at the end of the input.
+    #[error("unbalanced bracket '{found}' ({position})")]
+    UnbalancedBracket { position: Position, found: Symbol },
 }
 
 impl Error {
@@ -85,7 +132,99 @@ impl Error {
             Self::InvalidSymbolToken { position } => position,
             Self::InvalidVariableToken { position } => position,
             Self::InvalidWhitespaceToken { position } => position,
+            Self::InvalidRadix { position, .. } => position,
+            Self::InvalidControlChar { position, .. } => position,
+            Self::UnexpectedChar { position, .. } => position,
+            Self::ExpectedDigit { position } => position,
+            Self::InvalidUtf8 { position } => position,
+            Self::MultilineString { position } => position,
+            Self::TokenTooLong { position, .. } => position,
+            Self::UnbalancedBracket { position, .. } => position,
+        }
+    }
+
+    /// Renders a diagnostic message which points at where this error occurred in `source`.
+    ///
+    /// The caret under the offending line is aligned by reproducing the tabs in the line,
+    /// so the rendering is independent of the tab width of the terminal.
+    ///
+    /// The offset of the error position must be relative to the beginning of `source`.
+    /// For an error reported by a tokenizer made with
+    /// [`Tokenizer::with_base_position`](crate::Tokenizer::with_base_position),
+    /// use [`Error::render_diagnostic_with_base`] instead.
+    /// If `source` does not contain the position, a note is rendered in place of the offending line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let src = "foo(\"a\"\"b\")";
+    /// let error = Tokenizer::new(src).find_map(|t| t.err()).unwrap();
+    /// assert_eq!(error.render_diagnostic(src), r#"error: adjacent string literals without intervening white space
+    ///  --> <unknown>:1:8
+    ///   |
+    /// 1 | foo("a""b")
+    ///   |        ^
+    /// "#);
+    /// ```
+    pub fn render_diagnostic(&self, source: &str) -> String {
+        self.render_diagnostic_with_base(source, 0)
+    }
+
+    /// Same as [`Error::render_diagnostic`] except that `source` begins at the byte offset `base`.
+    ///
+    /// Use this for errors reported by a tokenizer made with
+    /// [`Tokenizer::with_base_position`](crate::Tokenizer::with_base_position),
+    /// passing the same offset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let src = "foo(\"a\"\"b\")";
+    /// let error = Tokenizer::new(src)
+    ///     .with_base_position(3, 5, 100)
+    ///     .find_map(|t| t.err())
+    ///     .unwrap();
+    /// assert_eq!(error.render_diagnostic_with_base(src, 100), r#"error: adjacent string literals without intervening white space
+    ///  --> <unknown>:3:12
+    ///   |
+    /// 3 | foo("a""b")
+    ///   |        ^
+    /// "#);
+    /// ```
+    pub fn render_diagnostic_with_base(&self, source: &str, base: usize) -> String {
+        let position = self.position();
+        let line_number = position.line().to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        // The location is shown in the `-->` line instead of the message.
+        let message = self.to_string();
+        let suffix = format!(" ({})", position);
+        let message = message.strip_suffix(&suffix).unwrap_or(&message);
+
+        let mut s = format!(
+            "error: {}\n{}--> {}\n{} |\n",
+            message, gutter, position, gutter
+        );
+        let line = position
+            .offset()
+            .checked_sub(base)
+            .and_then(|offset| crate::position::line_text_at(source, offset).map(|l| (l, offset)));
+        if let Some(((line, line_start), offset)) = line {
+            let caret_offset = offset - line_start;
+            let indent = line
+                .char_indices()
+                .take_while(|&(i, _)| i < caret_offset)
+                .map(|(_, c)| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            s += &format!("{} | {}\n{} | {}^\n", line_number, line, gutter, indent);
+        } else {
+            s += &format!("{} = note: the position is outside of the source\n", gutter);
         }
+        s
     }
 
     pub(crate) fn no_closing_quotation(position: Position) -> Self {
@@ -147,4 +286,36 @@ impl Error {
     pub(crate) fn invalid_whitespace_token(position: Position) -> Self {
         Self::InvalidWhitespaceToken { position }
     }
+
+    pub(crate) fn invalid_radix(position: Position, radix: u32) -> Self {
+        Self::InvalidRadix { position, radix }
+    }
+
+    pub(crate) fn invalid_control_char(position: Position, ch: char) -> Self {
+        Self::InvalidControlChar { position, ch }
+    }
+
+    pub(crate) fn unexpected_char(position: Position, ch: char) -> Self {
+        Self::UnexpectedChar { position, ch }
+    }
+
+    pub(crate) fn expected_digit(position: Position) -> Self {
+        Self::ExpectedDigit { position }
+    }
+
+    pub(crate) fn invalid_utf8(position: Position) -> Self {
+        Self::InvalidUtf8 { position }
+    }
+
+    pub(crate) fn multiline_string(position: Position) -> Self {
+        Self::MultilineString { position }
+    }
+
+    pub(crate) fn token_too_long(position: Position, limit: usize) -> Self {
+        Self::TokenTooLong { position, limit }
+    }
+
+    pub(crate) fn unbalanced_bracket(position: Position, found: Symbol) -> Self {
+        Self::UnbalancedBracket { position, found }
+    }
 }
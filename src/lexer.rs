@@ -1,3 +1,4 @@
+#[cfg(feature = "std")]
 use std::path::Path;
 
 use crate::{LexicalToken, Position, Result, Tokenizer};
@@ -18,6 +19,7 @@ where
     }
 
     /// Sets the file path of the succeeding tokens.
+    #[cfg(feature = "std")]
     pub fn set_filepath<P: AsRef<Path>>(&mut self, filepath: P) {
         self.0.set_filepath(filepath);
     }
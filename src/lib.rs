@@ -22,26 +22,50 @@
 //!
 //! [erl_scan]: http://erlang.org/doc/man/erl_scan.html
 //! [Data Types]: http://erlang.org/doc/reference_manual/data_types.html
+//!
+//! # Features
+//!
+//! - `std` (enabled by default): Enables the APIs depending on the standard library
+//!   (e.g., file paths of positions). Without this feature, this crate only requires `alloc`.
+//! - `arbitrary`: Implements `arbitrary::Arbitrary` for tokens and their values.
+#![no_std]
 #![warn(missing_docs)]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub use crate::document::TokenizedDocument;
 pub use crate::error::Error;
 pub use crate::hidden_token::HiddenToken;
 pub use crate::lexer::Lexer;
 pub use crate::lexical_token::LexicalToken;
-pub use crate::position::{Position, PositionRange};
-pub use crate::token::Token;
-pub use crate::tokenizer::Tokenizer;
+pub use crate::position::{are_adjacent, line_text, Position, PositionRange, Span};
+pub use crate::pretty::{pretty_print, PrettyOptions};
+pub use crate::token::{
+    kind_for_leading_char, render, whitespace_newline_count, whitespace_sequence, HighlightClass,
+    Token, TokenKind, TokenText,
+};
+pub use crate::tokenizer::{
+    atom_names, form_byte_ranges, is_valid, positions_of, token_count, unique_atom_names, Cursor,
+    Edit, Tokenizer,
+};
+pub use crate::visitor::{visit_tokens, TokenVisitor};
 
+pub mod parse;
 pub mod tokens;
 pub mod values;
 
+mod document;
 mod error;
 mod hidden_token;
 mod lexer;
 mod lexical_token;
 mod position;
+mod pretty;
 mod token;
 mod tokenizer;
 mod util;
+mod visitor;
 
 /// This crate specific `Result` type.
-pub type Result<T> = ::std::result::Result<T, Error>;
+pub type Result<T> = ::core::result::Result<T, Error>;
@@ -0,0 +1,99 @@
+//! Functions to parse Erlang literals outside of tokenization.
+use alloc::borrow::Cow;
+use num::BigUint;
+
+use crate::tokens::{FloatToken, IntegerToken};
+use crate::util;
+use crate::{Error, Position, PositionRange, Result, Token};
+
+/// Parses the whole text as an Erlang integer literal.
+///
+/// Both decimal (e.g., `123`, `1_000`) and based (e.g., `16#ff`, `2#1010`) integers are accepted.
+/// Unlike `IntegerToken::from_text`, trailing characters are not allowed.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::parse::parse_integer;
+///
+/// assert_eq!(parse_integer("16#ff").unwrap(), 255u32.into());
+/// assert_eq!(parse_integer("1_000").unwrap(), 1000u32.into());
+///
+/// assert!(parse_integer("1#0").is_err());
+/// assert!(parse_integer("123abc").is_err());
+/// ```
+pub fn parse_integer(text: &str) -> Result<BigUint> {
+    let token = IntegerToken::from_text(text, Position::new())?;
+    if token.text().len() != text.len() {
+        return Err(Error::invalid_integer_token(token.end_position()));
+    }
+    Ok(token.value().clone())
+}
+
+/// Parses the whole text as an Erlang number literal and returns the corresponding token.
+///
+/// Like [`Token::from_text`], the text is parsed as a float if its leading digits are
+/// followed by a decimal point and a digit, and as an integer otherwise.
+/// Unlike `Token::from_text`, trailing characters are not allowed.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{Position, TokenKind};
+/// use erl_tokenize::parse::parse_number;
+///
+/// assert_eq!(parse_number("1.5e3", Position::new()).unwrap().kind(), TokenKind::Float);
+/// assert_eq!(parse_number("16#ff", Position::new()).unwrap().kind(), TokenKind::Integer);
+///
+/// assert!(parse_number("1.5foo", Position::new()).is_err());
+/// ```
+pub fn parse_number(text: &str, pos: Position) -> Result<Token> {
+    let (token, error): (Token, fn(Position) -> Error) = if Token::maybe_float(text) {
+        (
+            FloatToken::from_text(text, pos)?.into(),
+            Error::invalid_float_token,
+        )
+    } else {
+        (
+            IntegerToken::from_text(text, pos)?.into(),
+            Error::invalid_integer_token,
+        )
+    };
+    if token.text().len() != text.len() {
+        return Err(error(token.end_position()));
+    }
+    Ok(token)
+}
+
+/// Parses a quoted content which is terminated by `terminator`.
+///
+/// `input` should start just after the opening delimiter.
+/// The result is the content with escape sequences processed and
+/// the byte offset of the terminator in `input`.
+/// If the content has no escape sequences, the returned content borrows `input`.
+///
+/// `pos` is the position of `input` and is used for error reporting.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::Position;
+/// use erl_tokenize::parse::parse_quotation;
+///
+/// let (content, end) = parse_quotation(Position::new(), r#"foo" bar"#, '"').unwrap();
+/// assert_eq!(content, "foo");
+/// assert_eq!(end, 3);
+///
+/// let (content, end) = parse_quotation(Position::new(), r"a\tb| bar", '|').unwrap();
+/// assert_eq!(content, "a\tb");
+/// assert_eq!(end, 4);
+///
+/// assert!(parse_quotation(Position::new(), "foo", '"').is_err());
+/// ```
+pub fn parse_quotation(
+    pos: Position,
+    input: &str,
+    terminator: char,
+) -> Result<(Cow<'_, str>, usize)> {
+    util::parse_quotation(pos, input, terminator)
+}
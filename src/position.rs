@@ -1,11 +1,25 @@
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
 use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
 use std::sync::Arc;
 
+use crate::Token;
+
 /// Position of token.
+///
+/// Note that the derived `Ord` (and `PartialOrd`) compares the file path first
+/// (positions without file path come first) and then the offset.
+///
+/// The file path is only available if the `std` feature is enabled.
+/// Use [`Position::cmp_offset`] to compare positions while ignoring their file paths.
 #[derive(
     Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
 )]
 pub struct Position {
+    #[cfg(feature = "std")]
     filepath: Option<Arc<PathBuf>>,
     offset: usize,
     line: usize,
@@ -15,6 +29,7 @@ impl Position {
     /// Returns an initial position.
     pub fn new() -> Position {
         Position {
+            #[cfg(feature = "std")]
             filepath: None,
             line: 1,
             column: 1,
@@ -23,10 +38,55 @@ impl Position {
     }
 
     /// Returns the file path where this token is located.
+    #[cfg(feature = "std")]
     pub fn filepath(&self) -> Option<&PathBuf> {
         self.filepath.as_ref().map(AsRef::as_ref)
     }
 
+    /// Returns the shared file path where this token is located.
+    ///
+    /// All positions derived from the same tokenizer share one `Arc`,
+    /// so [`Arc::ptr_eq`] can be used to cheaply check whether two positions belong to the same file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use erl_tokenize::{PositionRange, Tokenizer};
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo bar");
+    /// tokenizer.set_filepath("foo.erl");
+    /// let tokens = tokenizer.collect::<Result<Vec<_>, _>>().unwrap();
+    ///
+    /// let a = tokens[0].start_position();
+    /// let b = tokens[2].end_position();
+    /// assert!(Arc::ptr_eq(a.filepath_arc().unwrap(), b.filepath_arc().unwrap()));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn filepath_arc(&self) -> Option<&Arc<PathBuf>> {
+        self.filepath.as_ref()
+    }
+
+    /// Returns the textual representation of this position whose file path uses `/` as the separator.
+    ///
+    /// The `Display` implementation of `Position` prints the file path in the native form
+    /// (e.g., `a\b.erl:1:1` on Windows).
+    /// This method replaces backslashes in the path with forward slashes instead,
+    /// which is useful for tools expecting `file:line:column`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo");
+    /// tokenizer.set_filepath("src\\foo.erl");
+    /// assert_eq!(tokenizer.next_position().display_unix_path(), "src/foo.erl:1:1");
+    /// ```
+    pub fn display_unix_path(&self) -> String {
+        self.to_string().replace('\\', "/")
+    }
+
     /// Returns an offset from the beginning of the buffer.
     pub fn offset(&self) -> usize {
         self.offset
@@ -42,11 +102,80 @@ impl Position {
         self.column
     }
 
+    /// Compares this position with `other` only by their offsets (the file paths are ignored).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::cmp::Ordering;
+    /// use erl_tokenize::{PositionRange, Tokenizer};
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo bar");
+    /// tokenizer.set_filepath("b.erl");
+    /// let foo = tokenizer.next().unwrap().unwrap().start_position();
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo bar");
+    /// tokenizer.set_filepath("a.erl");
+    /// tokenizer.next(); // 'foo'
+    /// tokenizer.next(); // ' '
+    /// let bar = tokenizer.next().unwrap().unwrap().start_position();
+    ///
+    /// assert_eq!(foo.cmp(&bar), Ordering::Greater);
+    /// assert_eq!(foo.cmp_offset(&bar), Ordering::Less);
+    /// ```
+    pub fn cmp_offset(&self, other: &Position) -> Ordering {
+        self.offset.cmp(&other.offset)
+    }
+
+    /// Returns the position at the end of `text` which starts at this position.
+    ///
+    /// Like the positions of tokens, the line is advanced at each newline, and
+    /// the column is counted in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    ///
+    /// let pos = Position::new().advance("a\nbc");
+    /// assert_eq!(pos.offset(), 4);
+    /// assert_eq!(pos.line(), 2);
+    /// assert_eq!(pos.column(), 3);
+    /// ```
+    pub fn advance(&self, text: &str) -> Position {
+        self.clone().step_by_text(text)
+    }
+
     /// Sets the file path where this token is located.
+    #[cfg(feature = "std")]
     pub(crate) fn set_filepath<P: AsRef<Path>>(&mut self, path: P) {
         self.filepath = Some(Arc::new(path.as_ref().to_path_buf()));
     }
 
+    /// Returns the initial position of the same file.
+    pub(crate) fn rewind(&self) -> Position {
+        Position {
+            #[cfg(feature = "std")]
+            filepath: self.filepath.clone(),
+            ..Position::new()
+        }
+    }
+
+    /// Returns a position of the same file located at the given coordinates.
+    pub(crate) fn relocate(mut self, offset: usize, line: usize, column: usize) -> Position {
+        self.offset = offset;
+        self.line = line;
+        self.column = column;
+        self
+    }
+
+    /// Shifts a position by the given offset and line deltas (the column is unchanged).
+    pub(crate) fn shift(mut self, offset: isize, line: isize) -> Position {
+        self.offset = self.offset.wrapping_add_signed(offset);
+        self.line = self.line.wrapping_add_signed(line);
+        self
+    }
+
     /// Steps a position by the given width.
     pub(crate) fn step_by_width(mut self, witdh: usize) -> Position {
         self.offset += witdh;
@@ -87,22 +216,23 @@ impl Default for Position {
     }
 }
 
-impl std::fmt::Display for Position {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(
-            f,
-            "{}:{}:{}",
-            self.filepath
-                .as_ref()
-                .and_then(|f| f.to_str())
-                .unwrap_or("<unknown>"),
-            self.line,
-            self.column
-        )
+/// Formats as `filepath:line:column`, where the file path is printed in the native form
+/// (see also [`Position::display_unix_path`]).
+impl core::fmt::Display for Position {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        #[cfg(feature = "std")]
+        let filepath = self
+            .filepath
+            .as_ref()
+            .and_then(|f| f.to_str())
+            .unwrap_or("<unknown>");
+        #[cfg(not(feature = "std"))]
+        let filepath = "<unknown>";
+        write!(f, "{}:{}:{}", filepath, self.line, self.column)
     }
 }
 
-impl std::ops::Add<usize> for Position {
+impl core::ops::Add<usize> for Position {
     type Output = Self;
 
     fn add(self, rhs: usize) -> Self {
@@ -127,3 +257,123 @@ impl<T: PositionRange> PositionRange for Box<T> {
         (**self).end_position()
     }
 }
+
+/// Returns `true` if `b` starts exactly at the end of `a` (i.e., there is no gap between them).
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{are_adjacent, Tokenizer};
+///
+/// let tokens = Tokenizer::tokenize("foo(").unwrap();
+/// assert!(are_adjacent(&tokens[0], &tokens[1]));
+///
+/// let tokens = Tokenizer::tokenize("foo (").unwrap();
+/// assert!(!are_adjacent(&tokens[0], &tokens[2]));
+/// ```
+pub fn are_adjacent(a: &impl PositionRange, b: &impl PositionRange) -> bool {
+    a.end_position().offset() == b.start_position().offset()
+}
+
+/// Returns the line of `source` which contains `pos` (without the trailing newline).
+///
+/// A trailing `\r` of the line is also removed.
+/// If `pos` is out of range of `source`, this function returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{line_text, PositionRange, Tokenizer};
+///
+/// let src = "foo.\nbar(Baz).";
+/// let tokens = Tokenizer::tokenize(src).unwrap();
+/// assert_eq!(line_text(src, &tokens[0].start_position()), Some("foo."));
+/// assert_eq!(line_text(src, &tokens[5].start_position()), Some("bar(Baz)."));
+/// ```
+pub fn line_text<'a>(source: &'a str, pos: &Position) -> Option<&'a str> {
+    line_text_at(source, pos.offset()).map(|(line, _)| line)
+}
+
+/// Returns the line of `source` which contains the byte `offset`
+/// and the offset of the beginning of the line.
+pub(crate) fn line_text_at(source: &str, offset: usize) -> Option<(&str, usize)> {
+    if offset > source.len() || !source.is_char_boundary(offset) {
+        return None;
+    }
+    let start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let end = source[offset..]
+        .find('\n')
+        .map_or(source.len(), |i| offset + i);
+    let line = &source[start..end];
+    Some((line.strip_suffix('\r').unwrap_or(line), start))
+}
+
+/// Half-open range of positions.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{Span, Tokenizer};
+///
+/// let tokens = Tokenizer::new("foo bar").collect::<Result<Vec<_>, _>>().unwrap();
+/// let span = Span::from(&tokens[2]);
+/// assert_eq!(span.start().offset(), 4);
+/// assert_eq!(span.len(), 3);
+/// assert!(span.contains(6));
+/// assert!(!span.contains(7));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Span {
+    start: Position,
+    end: Position,
+}
+impl Span {
+    /// Makes a new `Span` instance.
+    pub fn new(start: Position, end: Position) -> Self {
+        Span { start, end }
+    }
+
+    /// Makes a new `Span` instance which covers `t`.
+    pub fn of<T: PositionRange>(t: &T) -> Self {
+        Span::new(t.start_position(), t.end_position())
+    }
+
+    /// Returns the (inclusive) start position of this span.
+    pub fn start(&self) -> &Position {
+        &self.start
+    }
+
+    /// Returns the (exclusive) end position of this span.
+    pub fn end(&self) -> &Position {
+        &self.end
+    }
+
+    /// Returns the byte length of this span.
+    pub fn len(&self) -> usize {
+        self.end.offset.saturating_sub(self.start.offset)
+    }
+
+    /// Returns `true` if this span is empty, otherwise `false`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if this span contains the given offset, otherwise `false`.
+    pub fn contains(&self, offset: usize) -> bool {
+        self.start.offset <= offset && offset < self.end.offset
+    }
+}
+impl PositionRange for Span {
+    fn start_position(&self) -> Position {
+        self.start.clone()
+    }
+
+    fn end_position(&self) -> Position {
+        self.end.clone()
+    }
+}
+impl From<&Token> for Span {
+    fn from(f: &Token) -> Self {
+        Span::of(f)
+    }
+}
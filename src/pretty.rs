@@ -0,0 +1,346 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::values::{Keyword, Symbol};
+use crate::Token;
+
+/// Options for [`pretty_print`].
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::PrettyOptions;
+///
+/// let options = PrettyOptions::new().indent_width(2).collapse_blank_lines(false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrettyOptions {
+    indent_width: usize,
+    collapse_blank_lines: bool,
+}
+impl PrettyOptions {
+    /// Makes a new `PrettyOptions` instance with the default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of spaces per indentation level (default: `4`).
+    pub fn indent_width(mut self, width: usize) -> Self {
+        self.indent_width = width;
+        self
+    }
+
+    /// Enables or disables collapsing consecutive blank lines into one (default: enabled).
+    pub fn collapse_blank_lines(mut self, enabled: bool) -> Self {
+        self.collapse_blank_lines = enabled;
+        self
+    }
+}
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        PrettyOptions {
+            indent_width: 4,
+            collapse_blank_lines: true,
+        }
+    }
+}
+
+/// Prints the tokens with consistent spacing and indentation.
+///
+/// Whitespace tokens in `tokens` are discarded except that their newlines are kept as line breaks.
+/// Spaces are inserted according to the classification of the tokens
+/// (e.g., around binary operators and after commas, but not inside brackets).
+/// A new line is started after each comment and each dot terminating a form.
+///
+/// Lines are indented by the nesting level of brackets, blocks (e.g., `case ... end`) and
+/// clause bodies (i.e., the tokens following `->`).
+///
+/// Note that this is a token-level heuristic and not a full-fledged code formatter.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{pretty_print, PrettyOptions, Tokenizer};
+///
+/// let tokens = Tokenizer::tokenize("foo(X)->[X,X+1].").unwrap();
+/// assert_eq!(pretty_print(&tokens, PrettyOptions::new()), "foo(X) -> [X, X + 1].\n");
+///
+/// let tokens = Tokenizer::tokenize("foo(X) ->\ncase X of\n1 -> a;\n_ -> b\nend.").unwrap();
+/// assert_eq!(pretty_print(&tokens, PrettyOptions::new().indent_width(2)),
+///            "foo(X) ->\n  case X of\n    1 -> a;\n    _ -> b\n  end.\n");
+/// ```
+pub fn pretty_print(tokens: &[Token], options: PrettyOptions) -> String {
+    // Pairs of a non-whitespace token and the number of newlines preceding it.
+    let mut items = Vec::new();
+    let mut newlines = 0;
+    for token in tokens {
+        match token {
+            Token::Whitespace(t) => newlines += t.newline_count(),
+            Token::Eof(_) => {}
+            _ => {
+                items.push((token, newlines));
+                newlines = 0;
+            }
+        }
+    }
+
+    let mut printer = Printer {
+        options,
+        buf: String::new(),
+        stack: Vec::new(),
+    };
+    let mut line_break = false;
+    for (i, &(token, newlines)) in items.iter().enumerate() {
+        let at_line_start = if i == 0 {
+            true
+        } else {
+            let mut newlines = newlines;
+            if printer.options.collapse_blank_lines {
+                newlines = newlines.min(2);
+            }
+            if line_break {
+                newlines = newlines.max(1);
+            }
+            for _ in 0..newlines {
+                printer.buf.push('\n');
+            }
+            if newlines == 0 && needs_space(&items, i, &printer.stack) {
+                printer.buf.push(' ');
+            }
+            newlines > 0
+        };
+
+        printer.enter(token);
+        if at_line_start {
+            let indent = printer.stack.len() * printer.options.indent_width;
+            printer.buf.push_str(&" ".repeat(indent));
+        }
+        printer.buf.push_str(token.text());
+        printer.leave(token, items.get(i + 1).map(|&(t, _)| t));
+
+        line_break = match token {
+            Token::Comment(_) => true,
+            Token::Symbol(t) if t.value() == Symbol::Dot => {
+                let terminator = match items.get(i + 1) {
+                    None => true,
+                    Some(&(next, newlines)) => {
+                        newlines > 0
+                            || matches!(next, Token::Comment(_))
+                            || !(printer.stack.iter().any(|f| matches!(f, Frame::Bracket(_)))
+                                || (i >= 2 && symbol(items[i - 2].0) == Some(Symbol::Sharp)))
+                    }
+                };
+                if terminator {
+                    printer.stack.clear();
+                }
+                terminator
+            }
+            _ => false,
+        };
+    }
+    if newlines > 0 || line_break {
+        printer.buf.push('\n');
+    }
+    printer.buf
+}
+
+#[derive(Debug)]
+struct Printer {
+    options: PrettyOptions,
+    buf: String,
+    stack: Vec<Frame>,
+}
+impl Printer {
+    fn enter(&mut self, token: &Token) {
+        match (symbol(token), keyword(token)) {
+            (
+                Some(
+                    Symbol::CloseParen
+                    | Symbol::CloseSquare
+                    | Symbol::CloseBrace
+                    | Symbol::DoubleRightAngle,
+                ),
+                _,
+            ) => {
+                if let Some(i) = self
+                    .stack
+                    .iter()
+                    .rposition(|f| matches!(f, Frame::Bracket(_)))
+                {
+                    self.stack.truncate(i);
+                }
+            }
+            (Some(Symbol::Semicolon), _) if self.stack.last() == Some(&Frame::Clause) => {
+                self.stack.pop();
+            }
+            (_, Some(Keyword::End)) => {
+                self.pop_clauses();
+                if matches!(self.stack.last(), Some(Frame::Block(_))) {
+                    self.stack.pop();
+                }
+            }
+            (_, Some(k @ (Keyword::Of | Keyword::Catch | Keyword::After | Keyword::Else))) => {
+                let block = self.stack.iter().rev().find(|f| **f != Frame::Clause);
+                let continues = match (block, k) {
+                    (Some(Frame::Block(b)), Keyword::Of) => {
+                        matches!(b, Keyword::Case | Keyword::Try)
+                    }
+                    (Some(Frame::Block(b)), Keyword::Catch) => *b == Keyword::Try,
+                    (Some(Frame::Block(b)), Keyword::After) => {
+                        matches!(b, Keyword::Try | Keyword::Receive)
+                    }
+                    (Some(Frame::Block(b)), Keyword::Else) => *b == Keyword::Maybe,
+                    _ => false,
+                };
+                if continues {
+                    self.pop_clauses();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn leave(&mut self, token: &Token, next: Option<&Token>) {
+        match (symbol(token), keyword(token)) {
+            (
+                Some(
+                    s @ (Symbol::OpenParen
+                    | Symbol::OpenSquare
+                    | Symbol::OpenBrace
+                    | Symbol::DoubleLeftAngle),
+                ),
+                _,
+            ) => self.stack.push(Frame::Bracket(s)),
+            (Some(Symbol::RightArrow), _) => self.stack.push(Frame::Clause),
+            (
+                _,
+                Some(
+                    k @ (Keyword::Case
+                    | Keyword::If
+                    | Keyword::Receive
+                    | Keyword::Begin
+                    | Keyword::Try
+                    | Keyword::Maybe),
+                ),
+            ) => self.stack.push(Frame::Block(k)),
+            (_, Some(Keyword::Fun)) if next.and_then(symbol) == Some(Symbol::OpenParen) => {
+                self.stack.push(Frame::Block(Keyword::Fun));
+            }
+            _ => {}
+        }
+    }
+
+    fn pop_clauses(&mut self) {
+        while self.stack.last() == Some(&Frame::Clause) {
+            self.stack.pop();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    Bracket(Symbol),
+    Block(Keyword),
+    Clause,
+}
+
+fn symbol(token: &Token) -> Option<Symbol> {
+    token.as_symbol_token().map(|t| t.value())
+}
+
+fn keyword(token: &Token) -> Option<Keyword> {
+    token.as_keyword_token().map(|t| t.value())
+}
+
+fn is_closing(token: &Token) -> bool {
+    matches!(
+        symbol(token),
+        Some(
+            Symbol::CloseParen
+                | Symbol::CloseSquare
+                | Symbol::CloseBrace
+                | Symbol::DoubleRightAngle
+        )
+    )
+}
+
+fn is_unary_sign(items: &[(&Token, usize)], i: usize) -> bool {
+    if !matches!(symbol(items[i].0), Some(Symbol::Hyphen | Symbol::Plus)) {
+        return false;
+    }
+    match items[..i]
+        .iter()
+        .rev()
+        .find(|(t, _)| !matches!(t, Token::Comment(_)))
+    {
+        None => true,
+        Some(&(prev, _)) => match prev {
+            Token::Symbol(_) => !is_closing(prev),
+            Token::Keyword(t) => t.value() != Keyword::End,
+            _ => false,
+        },
+    }
+}
+
+fn needs_space(items: &[(&Token, usize)], i: usize, stack: &[Frame]) -> bool {
+    let prev = items[i - 1].0;
+    let next = items[i].0;
+    if matches!(next, Token::Comment(_)) {
+        return true;
+    }
+
+    let in_binary = stack
+        .iter()
+        .rev()
+        .find_map(|f| match f {
+            Frame::Bracket(b) => Some(*b == Symbol::DoubleLeftAngle),
+            _ => None,
+        })
+        .unwrap_or(false);
+
+    match (symbol(prev), symbol(next)) {
+        (_, Some(Symbol::Comma | Symbol::Semicolon | Symbol::Dot)) => return false,
+        _ if is_closing(next) => return false,
+        (
+            Some(
+                Symbol::OpenParen
+                | Symbol::OpenSquare
+                | Symbol::OpenBrace
+                | Symbol::DoubleLeftAngle
+                | Symbol::Sharp
+                | Symbol::Question
+                | Symbol::DoubleQuestion
+                | Symbol::Colon,
+            ),
+            _,
+        ) => return false,
+        (_, Some(Symbol::Colon)) => return false,
+        (_, Some(Symbol::Sharp)) => {
+            return !(matches!(prev, Token::Variable(_)) || is_closing(prev));
+        }
+        (_, Some(Symbol::OpenBrace)) => {
+            return !(i >= 2 && symbol(items[i - 2].0) == Some(Symbol::Sharp));
+        }
+        (Some(Symbol::Dot), _) if i >= 3 && symbol(items[i - 3].0) == Some(Symbol::Sharp) => {
+            return false;
+        }
+        (_, Some(Symbol::OpenParen)) => {
+            return !(matches!(prev, Token::Atom(_) | Token::Variable(_))
+                || is_closing(prev)
+                || keyword(prev) == Some(Keyword::Fun));
+        }
+        (_, Some(Symbol::Slash)) => {
+            let arity = matches!(prev, Token::Atom(_))
+                && matches!(items.get(i + 1), Some((Token::Integer(_), _)));
+            return !(arity || in_binary);
+        }
+        (Some(Symbol::Slash), _) => {
+            let arity = i >= 2
+                && matches!(items[i - 2].0, Token::Atom(_))
+                && matches!(next, Token::Integer(_));
+            return !(arity || in_binary);
+        }
+        _ => {}
+    }
+    !is_unary_sign(items, i - 1)
+}
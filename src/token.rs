@@ -1,9 +1,16 @@
-use std::fmt;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
 
+use crate::tokenizer::TokenizerOptions;
 use crate::tokens::{
-    AtomToken, CharToken, CommentToken, FloatToken, IntegerToken, KeywordToken, SigilStringToken,
-    StringToken, SymbolToken, VariableToken, WhitespaceToken,
+    AtomToken, CharToken, CommentToken, EofToken, FloatToken, IntegerToken, KeywordToken,
+    SigilStringToken, StringToken, SymbolToken, VariableToken, WhitespaceToken,
 };
+use crate::util;
+use crate::values::{Keyword, Symbol, Whitespace};
 use crate::{Error, HiddenToken, LexicalToken, Position, PositionRange};
 
 /// Token.
@@ -13,6 +20,7 @@ pub enum Token {
     Atom(AtomToken),
     Char(CharToken),
     Comment(CommentToken),
+    Eof(EofToken),
     Float(FloatToken),
     Integer(IntegerToken),
     Keyword(KeywordToken),
@@ -22,7 +30,118 @@ pub enum Token {
     Variable(VariableToken),
     Whitespace(WhitespaceToken),
 }
+
+/// Token kind.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum TokenKind {
+    Atom,
+    Char,
+    Comment,
+    Eof,
+    Float,
+    Integer,
+    Keyword,
+    SigilString,
+    String,
+    Symbol,
+    Variable,
+    Whitespace,
+}
+impl TokenKind {
+    const ALL: [TokenKind; 12] = [
+        TokenKind::Atom,
+        TokenKind::Char,
+        TokenKind::Comment,
+        TokenKind::Eof,
+        TokenKind::Float,
+        TokenKind::Integer,
+        TokenKind::Keyword,
+        TokenKind::SigilString,
+        TokenKind::String,
+        TokenKind::Symbol,
+        TokenKind::Variable,
+        TokenKind::Whitespace,
+    ];
+
+    /// Returns the index of this kind in [`TokenKind::all`].
+    ///
+    /// The indices are dense (i.e., in `0..TokenKind::count()`), so they can be used to index
+    /// an array such as per-kind statistics.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{TokenKind, Tokenizer};
+    ///
+    /// let mut histogram = [0; TokenKind::count()];
+    /// for token in Tokenizer::new("foo(bar)") {
+    ///     histogram[token.unwrap().kind().index()] += 1;
+    /// }
+    /// assert_eq!(histogram[TokenKind::Atom.index()], 2);
+    /// assert_eq!(histogram[TokenKind::Symbol.index()], 2);
+    /// ```
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the number of the token kinds.
+    pub const fn count() -> usize {
+        Self::ALL.len()
+    }
+
+    /// Returns all the token kinds in the order of their indices.
+    pub const fn all() -> &'static [TokenKind] {
+        &Self::ALL
+    }
+}
+
+/// Coarse syntax-highlighting class of a token.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum HighlightClass {
+    Keyword,
+    Atom,
+    Variable,
+    Number,
+    String,
+    Char,
+    Comment,
+    Operator,
+    Punctuation,
+    Whitespace,
+}
+
 impl Token {
+    /// Returns the kind of this token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Position, Token, TokenKind};
+    ///
+    /// let pos = Position::new();
+    /// assert_eq!(Token::from_text("foo", pos.clone()).unwrap().kind(), TokenKind::Atom);
+    /// assert_eq!(Token::from_text("case", pos.clone()).unwrap().kind(), TokenKind::Keyword);
+    /// ```
+    pub fn kind(&self) -> TokenKind {
+        match *self {
+            Token::Atom(_) => TokenKind::Atom,
+            Token::Char(_) => TokenKind::Char,
+            Token::Comment(_) => TokenKind::Comment,
+            Token::Eof(_) => TokenKind::Eof,
+            Token::Float(_) => TokenKind::Float,
+            Token::Integer(_) => TokenKind::Integer,
+            Token::Keyword(_) => TokenKind::Keyword,
+            Token::SigilString(_) => TokenKind::SigilString,
+            Token::String(_) => TokenKind::String,
+            Token::Symbol(_) => TokenKind::Symbol,
+            Token::Variable(_) => TokenKind::Variable,
+            Token::Whitespace(_) => TokenKind::Whitespace,
+        }
+    }
+
     /// Tries to convert from any prefixes of the text to a token.
     ///
     /// # Examples
@@ -42,45 +161,54 @@ impl Token {
     /// assert_eq!(token.as_symbol_token().map(|t| t.value()), Some(Symbol::OpenSquare));
     /// ```
     pub fn from_text(text: &str, pos: Position) -> crate::Result<Self> {
+        Self::from_text_with_options(text, pos, &TokenizerOptions::default())
+    }
+
+    pub(crate) fn from_text_with_options(
+        text: &str,
+        pos: Position,
+        options: &TokenizerOptions,
+    ) -> crate::Result<Self> {
         let head = text
             .chars()
             .next()
             .ok_or_else(|| Error::missing_token(pos.clone()))?;
         match head {
-            ' ' | '\t' | '\r' | '\n' | '\u{A0}' => {
+            _ if head == options.comment_prefix => {
+                CommentToken::from_text_with_prefix(text, pos, head).map(Token::from)
+            }
+            _ if (options.atom_head_char)(head) => Self::atom_or_keyword(text, pos, options),
+            '\u{A0}' if options.ascii_whitespace_only => Err(Error::invalid_whitespace_token(pos)),
+            ' ' | '\t' | '\r' | '\n' | '\u{B}' | '\u{C}' | '\u{A0}' => {
                 WhitespaceToken::from_text(text, pos).map(Token::from)
             }
-            'A'..='Z' | '_' => VariableToken::from_text(text, pos).map(Token::from),
+            _ if util::is_variable_head_char(head) => {
+                VariableToken::from_text(text, pos).map(Token::from)
+            }
             '0'..='9' => {
-                let maybe_float =
-                    if let Some(i) = text.find(|c: char| !(c.is_ascii_digit() || c == '_')) {
-                        text.as_bytes()[i] == b'.'
-                            && text
-                                .as_bytes()
-                                .get(i + 1)
-                                .map_or(false, |c| (*c as char).is_ascii_digit())
-                    } else {
-                        false
-                    };
-                if maybe_float {
+                if Self::maybe_float(text) {
                     FloatToken::from_text(text, pos).map(Token::from)
                 } else {
                     IntegerToken::from_text(text, pos).map(Token::from)
                 }
             }
             '$' => CharToken::from_text(text, pos).map(Token::from),
-            '"' => StringToken::from_text(text, pos).map(Token::from),
+            '"' => {
+                let token = StringToken::from_text(text, pos)?;
+                if options.forbid_multiline_strings
+                    && !token.text().starts_with(r#"""""#)
+                    && token.raw_value().contains('\n')
+                {
+                    return Err(Error::multiline_string(token.start_position()));
+                }
+                Ok(Token::from(token))
+            }
             '\'' => AtomToken::from_text(text, pos).map(Token::from),
-            '%' => CommentToken::from_text(text, pos).map(Token::from),
             '~' => SigilStringToken::from_text(text, pos).map(Token::from),
+            _ if head.is_control() => Err(Error::invalid_control_char(pos, head)),
             _ => {
                 if head.is_alphabetic() {
-                    let atom = AtomToken::from_text(text, pos.clone())?;
-                    if let Ok(keyword) = KeywordToken::from_text(atom.text(), pos) {
-                        Ok(Token::from(keyword))
-                    } else {
-                        Ok(Token::from(atom))
-                    }
+                    Self::atom_or_keyword(text, pos, options)
                 } else {
                     SymbolToken::from_text(text, pos).map(Token::from)
                 }
@@ -88,6 +216,114 @@ impl Token {
         }
     }
 
+    /// Determines the kind of the token at the beginning of the text.
+    ///
+    /// Most kinds are decided only by looking at the leading characters,
+    /// so the token itself is not validated.
+    /// Symbols, control characters and atoms starting with non-lowercase alphabetic characters
+    /// fall back to full parsing.
+    pub(crate) fn kind_of_text(
+        text: &str,
+        pos: Position,
+        options: &TokenizerOptions,
+    ) -> crate::Result<TokenKind> {
+        let head = text
+            .chars()
+            .next()
+            .ok_or_else(|| Error::missing_token(pos.clone()))?;
+        let kind = match head {
+            _ if head == options.comment_prefix => TokenKind::Comment,
+            _ if (options.atom_head_char)(head) => {
+                let end = text
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(_, c)| !util::is_atom_non_head_char(c))
+                    .map_or(text.len(), |(i, _)| i);
+                if Keyword::from_name(&text[..end]).is_some_and(|k| options.is_keyword_enabled(k)) {
+                    TokenKind::Keyword
+                } else {
+                    TokenKind::Atom
+                }
+            }
+            '\u{A0}' if options.ascii_whitespace_only => {
+                return Err(Error::invalid_whitespace_token(pos));
+            }
+            ' ' | '\t' | '\r' | '\n' | '\u{B}' | '\u{C}' | '\u{A0}' => TokenKind::Whitespace,
+            _ if util::is_variable_head_char(head) => TokenKind::Variable,
+            '0'..='9' if Self::maybe_float(text) => TokenKind::Float,
+            '0'..='9' => TokenKind::Integer,
+            '$' => TokenKind::Char,
+            '"' => TokenKind::String,
+            '\'' => TokenKind::Atom,
+            '~' => TokenKind::SigilString,
+            _ => return Self::from_text_with_options(text, pos, options).map(|t| t.kind()),
+        };
+        Ok(kind)
+    }
+
+    pub(crate) fn maybe_float(text: &str) -> bool {
+        if let Some(i) = text.find(|c: char| !(c.is_ascii_digit() || c == '_')) {
+            text.as_bytes()[i] == b'.'
+                && text
+                    .as_bytes()
+                    .get(i + 1)
+                    .is_some_and(|c| (*c as char).is_ascii_digit())
+        } else {
+            false
+        }
+    }
+
+    fn atom_or_keyword(
+        text: &str,
+        pos: Position,
+        options: &TokenizerOptions,
+    ) -> crate::Result<Self> {
+        let atom = AtomToken::from_text_with_options(text, pos.clone(), options)?;
+        if let Ok(keyword) = KeywordToken::from_text_with_options(atom.text(), pos, options) {
+            Ok(Token::from(keyword))
+        } else {
+            Ok(Token::from(atom))
+        }
+    }
+
+    /// Returns the syntax-highlighting class of this token.
+    ///
+    /// Symbols are classified as `Operator` or `Punctuation` according to [`Symbol::category`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{HighlightClass, Position, Token};
+    ///
+    /// let pos = Position::new();
+    /// assert_eq!(Token::from_text("1.5", pos.clone()).unwrap().highlight_class(),
+    ///            HighlightClass::Number);
+    /// assert_eq!(Token::from_text("++", pos.clone()).unwrap().highlight_class(),
+    ///            HighlightClass::Operator);
+    /// assert_eq!(Token::from_text("(", pos.clone()).unwrap().highlight_class(),
+    ///            HighlightClass::Punctuation);
+    /// ```
+    pub fn highlight_class(&self) -> HighlightClass {
+        match *self {
+            Token::Atom(_) => HighlightClass::Atom,
+            Token::Char(_) => HighlightClass::Char,
+            Token::Comment(_) => HighlightClass::Comment,
+            Token::Eof(_) => HighlightClass::Whitespace,
+            Token::Float(_) | Token::Integer(_) => HighlightClass::Number,
+            Token::Keyword(_) => HighlightClass::Keyword,
+            Token::SigilString(_) | Token::String(_) => HighlightClass::String,
+            Token::Symbol(ref t) => {
+                if t.value().is_operator() {
+                    HighlightClass::Operator
+                } else {
+                    HighlightClass::Punctuation
+                }
+            }
+            Token::Variable(_) => HighlightClass::Variable,
+            Token::Whitespace(_) => HighlightClass::Whitespace,
+        }
+    }
+
     /// Returns the original textual representation of this token.
     ///
     /// # Examples
@@ -108,6 +344,7 @@ impl Token {
             Token::Atom(ref t) => t.text(),
             Token::Char(ref t) => t.text(),
             Token::Comment(ref t) => t.text(),
+            Token::Eof(ref t) => t.text(),
             Token::Float(ref t) => t.text(),
             Token::Integer(ref t) => t.text(),
             Token::Keyword(ref t) => t.text(),
@@ -119,9 +356,69 @@ impl Token {
         }
     }
 
+    /// Returns the length of the text of this token in bytes.
+    ///
+    /// This is equivalent to `self.text().len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Token, Position};
+    ///
+    /// let token = Token::from_text("comté", Position::new()).unwrap();
+    /// assert_eq!(token.byte_len(), 6);
+    /// ```
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    ///
+    /// This is equivalent to `self.text().chars().count()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Token, Position};
+    ///
+    /// let token = Token::from_text("comté", Position::new()).unwrap();
+    /// assert_eq!(token.char_len(), 5);
+    /// ```
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns the byte offset of the start position of this token.
+    ///
+    /// This is equivalent to `self.start_position().offset()`.
+    pub fn start_offset(&self) -> usize {
+        self.start_position().offset()
+    }
+
+    /// Compares two tokens by their start offsets.
+    ///
+    /// `Token` does not implement `Ord` because some token values (e.g., floats) are not totally ordered,
+    /// so use this function to sort tokens in source order instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Token, Tokenizer};
+    ///
+    /// let mut tokens = Tokenizer::tokenize("foo(1)").unwrap();
+    /// tokens.reverse();
+    /// tokens.sort_by(Token::cmp_by_position);
+    /// assert_eq!(tokens.iter().map(|t| t.text()).collect::<Vec<_>>(), ["foo", "(", "1", ")"]);
+    /// ```
+    pub fn cmp_by_position(&self, other: &Self) -> core::cmp::Ordering {
+        self.start_offset().cmp(&other.start_offset())
+    }
+
     /// Returns `true` if this is a lexical token, otherwise `false`.
+    ///
+    /// Note that an `EofToken` is neither a lexical token nor a hidden token.
     pub fn is_lexical_token(&self) -> bool {
-        !self.is_hidden_token()
+        !self.is_hidden_token() && !matches!(self, Token::Eof(_))
     }
 
     /// Returns `true` if this is a hidden token, otherwise `false`.
@@ -155,6 +452,86 @@ impl Token {
         }
     }
 
+    /// Returns `true` if this is a dot which terminates a form, otherwise `false`.
+    ///
+    /// In Erlang, such a dot is followed by a whitespace, a comment or the end of the input.
+    /// `following` is the token next to this one (`None` or an `EofToken` means the end of the input).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let tokens = Tokenizer::new("foo. R#r.f").collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(tokens[1].text(), ".");
+    /// assert!(tokens[1].is_terminator_dot(tokens.get(2)));
+    ///
+    /// assert_eq!(tokens[6].text(), ".");
+    /// assert!(!tokens[6].is_terminator_dot(tokens.get(7)));
+    /// ```
+    pub fn is_terminator_dot(&self, following: Option<&Token>) -> bool {
+        if !matches!(self, Token::Symbol(t) if t.value() == Symbol::Dot) {
+            return false;
+        }
+        !matches!(following, Some(t) if t.is_lexical_token())
+    }
+
+    /// Returns the textual representation of the `erl_scan` token term corresponding to this token.
+    ///
+    /// The output is an approximation of what `erl_scan:string/2` returns
+    /// (the location is only a line number).
+    /// Note that a `Symbol::Dot` is always rendered as `{dot,Line}`, and
+    /// a sigil string is rendered as a plain `string` term which has the sigil content.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Token, Position};
+    ///
+    /// let pos = Position::new();
+    ///
+    /// let token = Token::from_text("foo", pos.clone()).unwrap();
+    /// assert_eq!(token.to_erl_scan_term(), "{atom,1,foo}");
+    ///
+    /// let token = Token::from_text("(", pos.clone()).unwrap();
+    /// assert_eq!(token.to_erl_scan_term(), "{'(',1}");
+    /// ```
+    pub fn to_erl_scan_term(&self) -> String {
+        let line = self.start_position().line();
+        match *self {
+            Token::Atom(ref t) => {
+                let value = if util::is_unquoted_atom(t.value()) {
+                    t.value().to_owned()
+                } else {
+                    util::quote(t.value(), '\'')
+                };
+                format!("{{atom,{},{}}}", line, value)
+            }
+            Token::Char(ref t) => format!("{{char,{},{}}}", line, t.value() as u32),
+            Token::Comment(ref t) => format!("{{comment,{},{}}}", line, util::quote(t.text(), '"')),
+            Token::Eof(_) => format!("{{eof,{}}}", line),
+            Token::Float(ref t) => format!("{{float,{},{}}}", line, util::format_float(t.value())),
+            Token::Integer(ref t) => format!("{{integer,{},{}}}", line, t.value()),
+            Token::Keyword(ref t) => format!("{{'{}',{}}}", t.text(), line),
+            Token::SigilString(ref t) => {
+                let content = t.decoded_content().unwrap_or(Cow::Borrowed(t.value().1));
+                format!("{{string,{},{}}}", line, util::quote(&content, '"'))
+            }
+            Token::String(ref t) => format!("{{string,{},{}}}", line, util::quote(t.value(), '"')),
+            Token::Symbol(ref t) => {
+                if t.value() == Symbol::Dot {
+                    format!("{{dot,{}}}", line)
+                } else {
+                    format!("{{'{}',{}}}", t.text(), line)
+                }
+            }
+            Token::Variable(ref t) => format!("{{var,{},{}}}", line, util::quote(t.value(), '\'')),
+            Token::Whitespace(ref t) => {
+                format!("{{white_space,{},{}}}", line, util::quote(t.text(), '"'))
+            }
+        }
+    }
+
     /// Tries to return the reference to the inner `AtomToken`.
     pub fn as_atom_token(&self) -> Option<&AtomToken> {
         if let Token::Atom(ref t) = *self {
@@ -245,6 +622,15 @@ impl Token {
         }
     }
 
+    /// Tries to return the reference to the inner `EofToken`.
+    pub fn as_eof_token(&self) -> Option<&EofToken> {
+        if let Token::Eof(ref t) = *self {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
     /// Tries to return the inner `AtomToken`.
     #[allow(clippy::result_large_err)]
     pub fn into_atom_token(self) -> Result<AtomToken, Self> {
@@ -344,7 +730,196 @@ impl Token {
             Err(self)
         }
     }
+
+    /// Tries to return the inner `EofToken`.
+    #[allow(clippy::result_large_err)]
+    pub fn into_eof_token(self) -> Result<EofToken, Self> {
+        if let Token::Eof(t) = self {
+            Ok(t)
+        } else {
+            Err(self)
+        }
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        match *self {
+            Token::Atom(ref mut t) => t.set_position(pos),
+            Token::Char(ref mut t) => t.set_position(pos),
+            Token::Comment(ref mut t) => t.set_position(pos),
+            Token::Eof(ref mut t) => t.set_position(pos),
+            Token::Float(ref mut t) => t.set_position(pos),
+            Token::Integer(ref mut t) => t.set_position(pos),
+            Token::Keyword(ref mut t) => t.set_position(pos),
+            Token::SigilString(ref mut t) => t.set_position(pos),
+            Token::String(ref mut t) => t.set_position(pos),
+            Token::Symbol(ref mut t) => t.set_position(pos),
+            Token::Variable(ref mut t) => t.set_position(pos),
+            Token::Whitespace(ref mut t) => t.set_position(pos),
+        }
+    }
 }
+
+/// Concatenates the texts of the tokens.
+///
+/// The result exactly reproduces the original source code only if
+/// `tokens` are the unmodified output of a tokenizer (i.e., no tokens were synthesized,
+/// removed or relocated).
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{render, Tokenizer};
+///
+/// let src = r#"io:format("Hello"). % comment"#;
+/// let tokens = Tokenizer::tokenize(src).unwrap();
+/// assert_eq!(render(&tokens), src);
+/// ```
+pub fn render(tokens: &[Token]) -> String {
+    tokens.iter().map(Token::text).collect()
+}
+
+/// Returns the total number of newlines in the whitespace tokens.
+///
+/// Since a whitespace token consists of a single character, this is useful for
+/// counting the newlines of a run of whitespaces (see [`WhitespaceToken::newline_count`]).
+/// The other tokens (e.g., a string containing newlines) are not counted.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{whitespace_newline_count, Tokenizer};
+///
+/// let tokens = Tokenizer::tokenize("foo.\n\n\nbar.").unwrap();
+/// assert_eq!(whitespace_newline_count(&tokens), 3);
+/// assert_eq!(whitespace_newline_count(&tokens[..3]), 1);
+/// ```
+pub fn whitespace_newline_count(tokens: &[Token]) -> usize {
+    tokens
+        .iter()
+        .filter_map(Token::as_whitespace_token)
+        .map(WhitespaceToken::newline_count)
+        .sum()
+}
+
+/// Returns the sequence of the whitespaces in the whitespace tokens.
+///
+/// This is useful for getting the exact contents of a run of whitespaces
+/// (e.g., `Tab`, `Space` and `Newline`). The other tokens are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{whitespace_sequence, Tokenizer};
+/// use erl_tokenize::values::Whitespace;
+///
+/// let tokens = Tokenizer::tokenize("foo\t \n").unwrap();
+/// assert_eq!(whitespace_sequence(&tokens),
+///            [Whitespace::Tab, Whitespace::Space, Whitespace::Newline]);
+/// ```
+pub fn whitespace_sequence(tokens: &[Token]) -> Vec<Whitespace> {
+    tokens
+        .iter()
+        .filter_map(Token::as_whitespace_token)
+        .map(WhitespaceToken::value)
+        .collect()
+}
+
+/// Returns the kind of the token which would start with the character.
+///
+/// This mirrors the dispatch of [`Token::from_text`] with the default options.
+/// Since atoms and keywords cannot be distinguished by a single character, `TokenKind::Atom` is
+/// returned for both. Similarly, `TokenKind::Integer` is returned for digits even though
+/// the token may turn out to be a float.
+/// If the character cannot start any token (e.g., a control character or `` ` ``), this returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{kind_for_leading_char, TokenKind};
+///
+/// assert_eq!(kind_for_leading_char('$'), Some(TokenKind::Char));
+/// assert_eq!(kind_for_leading_char('f'), Some(TokenKind::Atom));
+/// assert_eq!(kind_for_leading_char('1'), Some(TokenKind::Integer));
+/// assert_eq!(kind_for_leading_char('`'), None);
+/// ```
+pub fn kind_for_leading_char(c: char) -> Option<TokenKind> {
+    let kind = match c {
+        '%' => TokenKind::Comment,
+        _ if util::is_atom_head_char(c) => TokenKind::Atom,
+        ' ' | '\t' | '\r' | '\n' | '\u{B}' | '\u{C}' | '\u{A0}' => TokenKind::Whitespace,
+        _ if util::is_variable_head_char(c) => TokenKind::Variable,
+        '0'..='9' => TokenKind::Integer,
+        '$' => TokenKind::Char,
+        '"' => TokenKind::String,
+        '\'' => TokenKind::Atom,
+        '~' => TokenKind::SigilString,
+        _ if c.is_control() => return None,
+        _ if c.is_alphabetic() => TokenKind::Atom,
+        _ => {
+            let mut buf = [0; 4];
+            SymbolToken::from_text(c.encode_utf8(&mut buf), Position::new()).ok()?;
+            TokenKind::Symbol
+        }
+    };
+    Some(kind)
+}
+
+/// Text made by concatenating the texts of tokens.
+///
+/// This is an owned counterpart of [`render`] which can be built by `collect()` or `extend()`.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{TokenText, Tokenizer};
+///
+/// let src = r#"io:format("Hello"). % comment"#;
+/// let text: TokenText = Tokenizer::tokenize(src).unwrap().into_iter().collect();
+/// assert_eq!(text.as_str(), src);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct TokenText(String);
+impl TokenText {
+    /// Makes a new empty `TokenText` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the concatenated text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Converts into the concatenated text.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+impl FromIterator<Token> for TokenText {
+    fn from_iter<I: IntoIterator<Item = Token>>(iter: I) -> Self {
+        let mut text = Self::new();
+        text.extend(iter);
+        text
+    }
+}
+impl Extend<Token> for TokenText {
+    fn extend<I: IntoIterator<Item = Token>>(&mut self, iter: I) {
+        for token in iter {
+            self.0.push_str(token.text());
+        }
+    }
+}
+impl From<TokenText> for String {
+    fn from(f: TokenText) -> Self {
+        f.0
+    }
+}
+impl fmt::Display for TokenText {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 impl From<AtomToken> for Token {
     fn from(f: AtomToken) -> Self {
         Token::Atom(f)
@@ -360,6 +935,11 @@ impl From<CommentToken> for Token {
         Token::Comment(f)
     }
 }
+impl From<EofToken> for Token {
+    fn from(f: EofToken) -> Self {
+        Token::Eof(f)
+    }
+}
 impl From<FloatToken> for Token {
     fn from(f: FloatToken) -> Self {
         Token::Float(f)
@@ -428,6 +1008,7 @@ impl PositionRange for Token {
             Token::Atom(ref t) => t.start_position(),
             Token::Char(ref t) => t.start_position(),
             Token::Comment(ref t) => t.start_position(),
+            Token::Eof(ref t) => t.start_position(),
             Token::Float(ref t) => t.start_position(),
             Token::Integer(ref t) => t.start_position(),
             Token::Keyword(ref t) => t.start_position(),
@@ -443,6 +1024,7 @@ impl PositionRange for Token {
             Token::Atom(ref t) => t.end_position(),
             Token::Char(ref t) => t.end_position(),
             Token::Comment(ref t) => t.end_position(),
+            Token::Eof(ref t) => t.end_position(),
             Token::Float(ref t) => t.end_position(),
             Token::Integer(ref t) => t.end_position(),
             Token::Keyword(ref t) => t.end_position(),
@@ -459,3 +1041,79 @@ impl fmt::Display for Token {
         self.text().fmt(f)
     }
 }
+
+#[cfg(feature = "arbitrary")]
+impl Token {
+    /// Makes a valid token of the given kind from the data provided by `u`.
+    ///
+    /// The resulting token is located at the initial position.
+    pub fn arbitrary_of_kind(
+        kind: TokenKind,
+        u: &mut arbitrary::Unstructured<'_>,
+    ) -> arbitrary::Result<Self> {
+        const VARIABLE_CHARS: &[u8] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz0123456789@";
+
+        let pos = Position::new();
+        let token = match kind {
+            TokenKind::Atom => {
+                let value = u
+                    .arbitrary::<&str>()?
+                    .chars()
+                    .filter(|c| !c.is_control())
+                    .take(255)
+                    .collect::<String>();
+                AtomToken::from_value(&value, pos)
+                    .expect("unreachable")
+                    .into()
+            }
+            TokenKind::Char => CharToken::from_value(u.arbitrary()?, pos).into(),
+            TokenKind::Comment => {
+                let value = u.arbitrary::<&str>()?.replace('\n', "");
+                CommentToken::from_value(&value, pos)
+                    .expect("unreachable")
+                    .into()
+            }
+            TokenKind::Float => {
+                let value: f64 = u.arbitrary()?;
+                let value = if value.is_finite() { value.abs() } else { 0.0 };
+                FloatToken::from_value(value, pos)
+                    .expect("unreachable")
+                    .into()
+            }
+            TokenKind::Integer => {
+                let value = num::BigUint::from_bytes_le(u.arbitrary()?);
+                IntegerToken::from_value(value, pos).into()
+            }
+            TokenKind::Keyword => KeywordToken::from_value(u.arbitrary()?, pos).into(),
+            TokenKind::SigilString => {
+                let text = format!("~s{}", util::quote(u.arbitrary()?, '"'));
+                SigilStringToken::from_text(&text, pos)
+                    .expect("unreachable")
+                    .into()
+            }
+            TokenKind::String => StringToken::from_value(u.arbitrary()?, pos).into(),
+            TokenKind::Symbol => SymbolToken::from_value(u.arbitrary()?, pos).into(),
+            TokenKind::Variable => {
+                let mut name = String::new();
+                name.push(*u.choose(&VARIABLE_CHARS[..27])? as char);
+                for _ in 0..u.arbitrary_len::<u8>()? {
+                    name.push(*u.choose(VARIABLE_CHARS)? as char);
+                }
+                VariableToken::from_value(&name, pos)
+                    .expect("unreachable")
+                    .into()
+            }
+            TokenKind::Whitespace => WhitespaceToken::from_value(u.arbitrary()?, pos).into(),
+            TokenKind::Eof => EofToken::new(pos).into(),
+        };
+        Ok(token)
+    }
+}
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Token {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let kind = u.arbitrary()?;
+        Self::arbitrary_of_kind(kind, u)
+    }
+}
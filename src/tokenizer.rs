@@ -1,6 +1,16 @@
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::str;
+#[cfg(feature = "std")]
 use std::path::Path;
 
-use crate::{Position, PositionRange, Result, Token};
+use crate::tokens::{AtomToken, CommentToken, EofToken, StringToken, WhitespaceToken};
+use crate::util;
+use crate::values::{Keyword, Symbol, Whitespace};
+use crate::{Error, Position, PositionRange, Result, Token, TokenKind};
 
 /// Tokenizer.
 ///
@@ -17,10 +27,18 @@ use crate::{Position, PositionRange, Result, Token};
 /// assert_eq!(tokens.iter().map(|t| t.text()).collect::<Vec<_>>(),
 ///            ["io", ":", "format", "(", r#""Hello""#, ")", "."]);
 /// ```
+///
+/// Note that the position of a tokenizer is not advanced when an error is returned.
+/// So, a loop over a tokenizer never ends on malformed input unless the loop recovers
+/// from the error (e.g., by calling [`Tokenizer::consume_char`]) or
+/// the strict mode is enabled (see [`Tokenizer::strict_mode`]).
 #[derive(Debug)]
 pub struct Tokenizer<T> {
     text: T,
     next_pos: Position,
+    options: TokenizerOptions,
+    failed: bool,
+    eof_emitted: bool,
 }
 impl<T> Tokenizer<T>
 where
@@ -32,10 +50,236 @@ where
         Tokenizer {
             text,
             next_pos: init_pos,
+            options: TokenizerOptions::default(),
+            failed: false,
+            eof_emitted: false,
         }
     }
 
+    /// Enables or disables the escript mode (default: disabled).
+    ///
+    /// In the escript mode, a shebang line (e.g., `#!/usr/bin/env escript`) at the beginning of
+    /// the text is tokenized as a comment token (see [`CommentToken::is_shebang`]).
+    /// Note that a `%%! ...` line is a normal comment regardless of this mode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let src = "#!/usr/bin/env escript\nmain(_) -> ok.";
+    /// let tokens = Tokenizer::new(src).escript_mode(true).collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(tokens[0].text(), "#!/usr/bin/env escript");
+    /// assert!(tokens[0].as_comment_token().is_some_and(|t| t.is_shebang()));
+    /// ```
+    pub fn escript_mode(mut self, enabled: bool) -> Self {
+        self.options.escript = enabled;
+        self
+    }
+
+    /// Enables or disables the strict mode (default: disabled).
+    ///
+    /// In the strict mode, this tokenizer returns an error only once and
+    /// then stops the iteration (i.e., it returns `None`) until the position is reset
+    /// by [`Tokenizer::set_position`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo \"bar").strict_mode(true);
+    /// assert!(tokenizer.next().unwrap().is_ok());
+    /// assert!(tokenizer.next().unwrap().is_ok());
+    /// assert!(tokenizer.next().unwrap().is_err());
+    /// assert!(tokenizer.next().is_none());
+    /// ```
+    pub fn strict_mode(mut self, enabled: bool) -> Self {
+        self.options.strict = enabled;
+        self
+    }
+
+    /// Sets the predicate used to decide whether a character can start an unquoted atom.
+    ///
+    /// This is useful for tokenizing alternative Erlang dialects.
+    /// The default predicate accepts lowercase letters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let tokens = Tokenizer::new("$foo")
+    ///     .with_atom_head_char(|c| c == '$' || c.is_lowercase())
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(tokens[0].as_atom_token().map(|t| t.value()), Some("$foo"));
+    /// ```
+    pub fn with_atom_head_char(mut self, f: fn(char) -> bool) -> Self {
+        self.options.atom_head_char = f;
+        self
+    }
+
+    /// Sets the maximum byte length of a token (default: unlimited).
+    ///
+    /// If a token exceeds the limit, this tokenizer yields `Error::TokenTooLong`
+    /// located at the start of the token.
+    /// This guards against pathological inputs (e.g., a huge unterminated string)
+    /// because the tokenizer never scans much more than `limit` bytes for a token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Error, Tokenizer};
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo foobar").max_token_bytes(4);
+    /// assert!(tokenizer.next().unwrap().is_ok());
+    /// assert!(tokenizer.next().unwrap().is_ok());
+    /// assert!(matches!(tokenizer.next(), Some(Err(Error::TokenTooLong { limit: 4, .. }))));
+    /// ```
+    pub fn max_token_bytes(mut self, limit: usize) -> Self {
+        self.options.max_token_bytes = Some(limit);
+        self
+    }
+
+    /// Sets the position of the beginning of the text (default: offset `0`, line `1` and column `1`).
+    ///
+    /// This is useful for tokenizing a snippet embedded in a larger document
+    /// (e.g., a code block in Markdown): all the positions of the tokens and errors,
+    /// as well as [`Tokenizer::next_position`] and [`Tokenizer::set_position`],
+    /// are in the coordinates of the host document.
+    /// The `column` only applies to the first line of the text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{PositionRange, Tokenizer};
+    ///
+    /// let tokens = Tokenizer::new("foo\n bar")
+    ///     .with_base_position(10, 5, 100)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// let pos = tokens[0].start_position();
+    /// assert_eq!((pos.line(), pos.column(), pos.offset()), (10, 5, 100));
+    /// let pos = tokens[3].start_position();
+    /// assert_eq!((pos.line(), pos.column(), pos.offset()), (11, 2, 105));
+    /// ```
+    pub fn with_base_position(mut self, line: usize, column: usize, offset: usize) -> Self {
+        self.options.base = (offset, line, column);
+        self.next_pos = self.start_position();
+        self
+    }
+
+    /// Enables or disables rejecting non-ASCII whitespaces (default: disabled).
+    ///
+    /// If enabled, a no-break space (`\u{A0}`) results in `Error::InvalidWhitespaceToken`
+    /// instead of a whitespace token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Error, Tokenizer};
+    ///
+    /// let mut tokenizer = Tokenizer::new("a\u{A0}b").ascii_whitespace_only(true);
+    /// assert!(tokenizer.next().unwrap().is_ok());
+    /// assert!(matches!(tokenizer.next(), Some(Err(Error::InvalidWhitespaceToken { .. }))));
+    /// ```
+    pub fn ascii_whitespace_only(mut self, enabled: bool) -> Self {
+        self.options.ascii_whitespace_only = enabled;
+        self
+    }
+
+    /// Enables or disables rejecting strings which contain raw newlines (default: disabled).
+    ///
+    /// If enabled, a double-quoted string whose content includes a newline character
+    /// (not an escape sequence `\n`) results in `Error::MultilineString`.
+    /// Triple-quoted strings are always allowed.
+    /// Erlang itself accepts such strings, so this is intended for style checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Error, Tokenizer};
+    ///
+    /// let src = "\"foo\nbar\"";
+    /// assert!(Tokenizer::new(src).next().unwrap().is_ok());
+    ///
+    /// let mut tokenizer = Tokenizer::new(src).forbid_multiline_strings(true);
+    /// assert!(matches!(tokenizer.next(), Some(Err(Error::MultilineString { .. }))));
+    /// ```
+    pub fn forbid_multiline_strings(mut self, enabled: bool) -> Self {
+        self.options.forbid_multiline_strings = enabled;
+        self
+    }
+
+    /// Enables or disables the `maybe_expr` feature (default: enabled).
+    ///
+    /// If disabled, `maybe` and `else` are tokenized as atoms instead of keywords
+    /// like the Erlang compiler without `-feature(maybe_expr, enable)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{TokenKind, Tokenizer};
+    ///
+    /// let token = Tokenizer::new("maybe").next().unwrap().unwrap();
+    /// assert_eq!(token.kind(), TokenKind::Keyword);
+    ///
+    /// let token = Tokenizer::new("maybe").enable_maybe_feature(false).next().unwrap().unwrap();
+    /// assert_eq!(token.kind(), TokenKind::Atom);
+    /// ```
+    pub fn enable_maybe_feature(mut self, enabled: bool) -> Self {
+        self.options.maybe_feature = enabled;
+        self
+    }
+
+    /// Enables or disables the EOF token (default: disabled).
+    ///
+    /// If enabled, this tokenizer yields a single [`EofToken`] after the last token.
+    /// The token is zero-width and is located at the end of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{PositionRange, Tokenizer};
+    ///
+    /// let tokens = Tokenizer::new("foo.")
+    ///     .with_eof_token(true)
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(tokens.len(), 3);
+    /// assert!(tokens[2].as_eof_token().is_some());
+    /// assert_eq!(tokens[2].start_position().offset(), 4);
+    /// ```
+    pub fn with_eof_token(mut self, enabled: bool) -> Self {
+        self.options.eof = enabled;
+        self
+    }
+
+    /// Sets the character which starts a comment (default: `%`).
+    ///
+    /// This is useful for tokenizing Erlang-like inputs which use non-standard comment markers.
+    /// Note that the default comment character `%` is no longer treated specially
+    /// once another character is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let tokens = Tokenizer::new("# foo\nbar")
+    ///     .with_comment_prefix('#')
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(tokens[0].as_comment_token().map(|t| t.value()), Some(" foo"));
+    /// ```
+    pub fn with_comment_prefix(mut self, prefix: char) -> Self {
+        self.options.comment_prefix = prefix;
+        self
+    }
+
     /// Sets the file path of the succeeding tokens.
+    #[cfg(feature = "std")]
     pub fn set_filepath<P: AsRef<Path>>(&mut self, filepath: P) {
         self.next_pos.set_filepath(filepath);
     }
@@ -81,6 +325,114 @@ where
         self.next_pos.clone()
     }
 
+    /// Makes a [`Cursor`] which starts at the current position of this tokenizer.
+    ///
+    /// The cursor borrows the text of this tokenizer, so it can be used for speculative parsing
+    /// without cloning the owned text. To commit the consumed tokens, pass [`Cursor::position`] to
+    /// [`Tokenizer::set_position`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new(String::from("foo bar"));
+    /// let mut cursor = tokenizer.cursor();
+    /// assert_eq!(cursor.next().unwrap().unwrap().text(), "foo");
+    /// assert_eq!(cursor.peek().unwrap().unwrap().text(), " ");
+    ///
+    /// let position = cursor.position();
+    /// tokenizer.set_position(position);
+    /// assert_eq!(tokenizer.next().unwrap().unwrap().text(), " ");
+    /// ```
+    pub fn cursor(&self) -> Cursor<'_> {
+        Cursor {
+            inner: Tokenizer {
+                text: self.text.as_ref(),
+                next_pos: self.next_pos.clone(),
+                options: self.options.clone(),
+                failed: self.failed,
+                eof_emitted: self.eof_emitted,
+            },
+        }
+    }
+
+    /// Returns the ratio of the already scanned text to the whole text (`0.0..=1.0`).
+    ///
+    /// If the text is empty, this method returns `1.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo bar");
+    /// assert_eq!(tokenizer.progress(), 0.0);
+    ///
+    /// tokenizer.next(); // 'foo'
+    /// let progress = tokenizer.progress();
+    /// assert!(0.0 < progress && progress < 1.0);
+    ///
+    /// tokenizer.next(); // ' '
+    /// assert!(progress < tokenizer.progress());
+    ///
+    /// tokenizer.next(); // 'bar'
+    /// assert_eq!(tokenizer.progress(), 1.0);
+    ///
+    /// assert_eq!(Tokenizer::new("").progress(), 1.0);
+    /// ```
+    pub fn progress(&self) -> f64 {
+        let len = self.text.as_ref().len();
+        if len == 0 {
+            1.0
+        } else {
+            (self.text_offset() as f64 / len as f64).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Returns the kind of the next token without consuming it.
+    ///
+    /// The kind is determined from the leading characters where possible,
+    /// so this method neither allocates nor validates the whole token
+    /// (e.g., `"foo` is reported as `TokenKind::String` even though the closing quotation is missing).
+    /// Symbols, control characters and atoms starting with non-lowercase alphabetic characters
+    /// fall back to parsing the whole token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{TokenKind, Tokenizer};
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo(Bar, 1.5)");
+    /// assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Atom);
+    ///
+    /// tokenizer.next(); // 'foo'
+    /// assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Symbol);
+    ///
+    /// tokenizer.next(); // '('
+    /// assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Variable);
+    ///
+    /// assert!(Tokenizer::new("").peek_kind().is_none());
+    /// ```
+    pub fn peek_kind(&self) -> Option<Result<TokenKind>> {
+        let text = self.text.as_ref();
+        if self.failed {
+            return None;
+        }
+        if self.text_offset() >= text.len() {
+            return (self.options.eof && !self.eof_emitted).then_some(Ok(TokenKind::Eof));
+        }
+        let text = &text[self.text_offset()..];
+        if self.options.escript && self.text_offset() == 0 && text.starts_with("#!") {
+            return Some(Ok(TokenKind::Comment));
+        }
+        Some(Token::kind_of_text(
+            text,
+            self.next_pos.clone(),
+            &self.options,
+        ))
+    }
+
     /// Sets the current position.
     ///
     /// Note that it's the responsibility of the user to specify a valid position.
@@ -110,6 +462,8 @@ where
     /// ```
     pub fn set_position(&mut self, position: Position) {
         self.next_pos = position;
+        self.failed = false;
+        self.eof_emitted = false;
     }
 
     /// Consumes the next char.
@@ -130,36 +484,1206 @@ where
     /// assert_eq!(tokenizer.next_position().offset(), 1);
     /// ```
     pub fn consume_char(&mut self) -> Option<char> {
-        if let Some(c) = self.text.as_ref()[self.next_pos.offset()..].chars().next() {
+        if let Some(c) = self.text.as_ref()[self.text_offset()..].chars().next() {
             self.next_pos = self.next_pos.clone().step_by_char(c);
             Some(c)
         } else {
             None
         }
     }
-}
-impl<T> Iterator for Tokenizer<T>
-where
-    T: AsRef<str>,
-{
-    type Item = Result<Token>;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.next_pos.offset() >= self.text.as_ref().len() {
+
+    fn start_position(&self) -> Position {
+        let (offset, line, column) = self.options.base;
+        self.next_pos.rewind().relocate(offset, line, column)
+    }
+
+    fn text_offset(&self) -> usize {
+        self.next_pos.offset().wrapping_sub(self.options.base.0)
+    }
+
+    /// Re-tokenizes the text of this tokenizer incrementally.
+    ///
+    /// The text of this tokenizer must be the result of applying `edit` to the old text
+    /// from which `old_tokens` were obtained.
+    ///
+    /// The old tokens preceding the last newline token before the edit are reused as they are.
+    /// Then, the text is re-scanned from there until reaching the beginning of a line,
+    /// located after the edit, where an old token also starts.
+    /// The remaining old tokens are reused with their positions shifted.
+    ///
+    /// After this call, this tokenizer points to the end of the text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Edit, Tokenizer};
+    ///
+    /// let old_text = "foo() -> 1.\nbar() -> 2.\n";
+    /// let old_tokens = Tokenizer::new(old_text).collect::<Result<Vec<_>, _>>().unwrap();
+    ///
+    /// let edit = Edit::new(9..10, "10");
+    /// let mut tokenizer = Tokenizer::new("foo() -> 10.\nbar() -> 2.\n");
+    /// let tokens = tokenizer.retokenize(&edit, &old_tokens).unwrap();
+    /// assert_eq!(tokens[6].text(), "10");
+    /// assert_eq!(tokens.len(), old_tokens.len());
+    /// ```
+    pub fn retokenize(&mut self, edit: &Edit, old_tokens: &[Token]) -> Result<Vec<Token>> {
+        let is_stable_boundary = |t: &Token| {
+            matches!(t, Token::Whitespace(t) if t.value() == Whitespace::Newline)
+                && t.end_position().offset() <= self.options.base.0 + edit.range.start
+        };
+        let reused = old_tokens
+            .iter()
+            .rposition(is_stable_boundary)
+            .map_or(0, |i| i + 1);
+        let mut tokens = old_tokens[..reused].to_vec();
+        let pos = tokens
+            .last()
+            .map_or_else(|| self.start_position(), |t| t.end_position());
+        self.set_position(pos);
+
+        let offset_delta = edit.text.len() as isize - edit.range.len() as isize;
+        let edit_end = self.options.base.0 + edit.range.start + edit.text.len();
+        loop {
+            let pos = self.next_position();
+            if pos.offset() > edit_end && pos.column() == 1 {
+                let old_offset = pos.offset().wrapping_add_signed(-offset_delta);
+                if let Ok(i) =
+                    old_tokens.binary_search_by_key(&old_offset, |t| t.start_position().offset())
+                {
+                    let line_delta =
+                        pos.line() as isize - old_tokens[i].start_position().line() as isize;
+                    tokens.extend(old_tokens[i..].iter().map(|t| {
+                        let mut t = t.clone();
+                        t.set_position(t.start_position().shift(offset_delta, line_delta));
+                        t
+                    }));
+                    if let Some(t) = tokens.last() {
+                        self.next_pos = t.end_position();
+                    }
+                    break;
+                }
+            }
+            match self.next() {
+                None => break,
+                Some(token) => tokens.push(token?),
+            }
+        }
+        Ok(tokens)
+    }
+
+    /// Reads the tokens of the next form, up to and including the dot terminating it.
+    ///
+    /// Unlike consuming this tokenizer as an iterator, the tokenizer can be used to continue
+    /// tokenization after this call.
+    /// The whitespaces and comments following the dot are left for the succeeding form.
+    /// If the input ends without a terminating dot, the remaining tokens are returned as the last form.
+    ///
+    /// If an error occurs, the tokens read so far are discarded and
+    /// this tokenizer remains positioned at the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let mut tokenizer = Tokenizer::new("foo() -> ok.\nbar() -> ok.");
+    /// let form = tokenizer.next_form().unwrap().unwrap();
+    /// assert_eq!(form.iter().map(|t| t.text()).collect::<Vec<_>>(),
+    ///            ["foo", "(", ")", " ", "->", " ", "ok", "."]);
+    /// assert_eq!(tokenizer.next_position().offset(), 12);
+    ///
+    /// let form = tokenizer.next_form().unwrap().unwrap();
+    /// assert_eq!(form.first().map(|t| t.text()), Some("\n"));
+    /// assert!(tokenizer.next_form().is_none());
+    /// ```
+    pub fn next_form(&mut self) -> Option<Result<Vec<Token>>> {
+        let mut tokens = Vec::new();
+        while let Some(token) = self.next() {
+            let token = match token {
+                Err(e) => return Some(Err(e)),
+                Ok(t) => t,
+            };
+            let is_terminator = matches!(token, Token::Symbol(ref t) if t.value() == Symbol::Dot)
+                && token.is_terminator_dot(self.cursor().next().and_then(|t| t.ok()).as_ref());
+            tokens.push(token);
+            if is_terminator {
+                break;
+            }
+        }
+        if tokens.is_empty() {
             None
         } else {
-            let text = unsafe {
-                self.text
-                    .as_ref()
-                    .get_unchecked(self.next_pos.offset()..self.text.as_ref().len())
-            };
-            let cur_pos = self.next_pos.clone();
-            match Token::from_text(text, cur_pos) {
-                Err(e) => Some(Err(e)),
-                Ok(t) => {
-                    self.next_pos = t.end_position();
-                    Some(Ok(t))
-                }
+            Some(Ok(tokens))
+        }
+    }
+
+    /// Makes an iterator which folds prefix signs into the following number tokens.
+    ///
+    /// A `-` or `+` symbol is regarded as a prefix sign if it is immediately followed by
+    /// an integer or a float token, and it is located at the beginning of the input or
+    /// preceded by a symbol (except closing brackets) or a keyword (except `end`).
+    /// The `text()` of a folded token includes the sign, while its `value()` remains
+    /// the absolute value (use `is_negative()` to check the sign).
+    ///
+    /// Note that this is a heuristic and the tokenizer never folds signs by default.
+    /// The iteration stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let tokens = Tokenizer::new("[-1, 3 - 4]").fold_signs().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(tokens.iter().map(|t| t.text()).collect::<Vec<_>>(),
+    ///            ["[", "-1", ",", " ", "3", " ", "-", " ", "4", "]"]);
+    /// ```
+    pub fn fold_signs(self) -> impl Iterator<Item = Result<Token>> {
+        FoldSigns {
+            inner: self,
+            pending: None,
+            prefix_position: true,
+            done: false,
+        }
+    }
+
+    /// Makes an iterator which groups consecutive tokens by the line numbers where they start.
+    ///
+    /// Each item is a pair of a line number and the tokens starting at that line.
+    /// A token spanning multiple lines (e.g., a multi-line string) belongs to its starting line.
+    ///
+    /// Note that the iteration stops after the first tokenization error
+    /// (the tokens preceding the error in the same line are yielded before it).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let lines = Tokenizer::new("foo.\n\nbar.").by_line().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(lines[0].0, 1);
+    /// assert_eq!(lines[0].1.iter().map(|t| t.text()).collect::<Vec<_>>(), ["foo", ".", "\n"]);
+    /// assert_eq!(lines[1].0, 2);
+    /// assert_eq!(lines[2].0, 3);
+    /// ```
+    pub fn by_line(self) -> impl Iterator<Item = Result<(usize, Vec<Token>)>> {
+        ByLine {
+            inner: self,
+            pending: None,
+            done: false,
+        }
+    }
+
+    /// Makes an iterator which checks that brackets are balanced while tokenizing.
+    ///
+    /// Pairs of brackets are determined by [`Symbol::matching`].
+    /// If a closing bracket does not match the innermost opening one,
+    /// or some brackets are left unclosed at the end of the input,
+    /// the iterator yields `Error::UnbalancedBracket` and then stops.
+    /// The iteration also stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Error, Tokenizer};
+    ///
+    /// assert!(Tokenizer::new("[{a}, (b)]").check_brackets().all(|t| t.is_ok()));
+    ///
+    /// let error = Tokenizer::new("foo(]").check_brackets().find_map(|t| t.err()).unwrap();
+    /// assert!(matches!(error, Error::UnbalancedBracket { .. }));
+    /// assert_eq!(error.position().offset(), 4);
+    /// ```
+    pub fn check_brackets(self) -> impl Iterator<Item = Result<Token>> {
+        CheckBrackets {
+            inner: self,
+            stack: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Makes an iterator which pairs each lexical token with its leading trivia.
+    ///
+    /// Trivia are the hidden tokens (i.e., whitespaces and comments) preceding a lexical token.
+    /// The last item is the trailing trivia paired with an [`EofToken`] located at the end of the input.
+    ///
+    /// Note that the iteration stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let items = Tokenizer::new("foo bar ").with_trivia().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(items.len(), 3);
+    /// assert_eq!(items[1].0.iter().map(|t| t.text()).collect::<Vec<_>>(), [" "]);
+    /// assert_eq!(items[1].1.text(), "bar");
+    /// assert!(items[2].1.as_eof_token().is_some());
+    /// ```
+    pub fn with_trivia(self) -> impl Iterator<Item = Result<(Vec<Token>, Token)>> {
+        WithTrivia {
+            inner: self,
+            done: false,
+        }
+    }
+
+    /// Makes an iterator which pairs each lexical token with the size of its preceding gap.
+    ///
+    /// The gap is the number of bytes of the whitespaces immediately preceding the token
+    /// (`0` if the token is adjacent to the previous one).
+    /// Comments are not yielded, and a comment resets the gap (i.e., only the whitespaces after it are counted).
+    ///
+    /// Note that the iteration stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let items = Tokenizer::new("foo  bar(1)").gaps().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(items.iter().map(|(t, n)| (t.text(), *n)).collect::<Vec<_>>(),
+    ///            [("foo", 0), ("bar", 2), ("(", 0), ("1", 0), (")", 0)]);
+    /// ```
+    pub fn gaps(self) -> impl Iterator<Item = Result<(Token, usize)>> {
+        Gaps {
+            inner: self,
+            done: false,
+        }
+    }
+
+    /// Makes an iterator which yields lexical tokens together with their indices.
+    ///
+    /// The index counts only lexical tokens, so hidden tokens (and `EofToken`) are skipped and
+    /// do not advance it. An error is paired with the index the next lexical token would have.
+    ///
+    /// Note that the iteration stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let items = Tokenizer::new("foo (1) % bar")
+    ///     .enumerate_lexical()
+    ///     .map(|(i, t)| (i, t.unwrap().text().to_owned()))
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(items, [(0, "foo".to_owned()), (1, "(".to_owned()), (2, "1".to_owned()), (3, ")".to_owned())]);
+    /// ```
+    pub fn enumerate_lexical(self) -> impl Iterator<Item = (usize, Result<Token>)> {
+        EnumerateLexical {
+            inner: self,
+            index: 0,
+            done: false,
+        }
+    }
+
+    /// Makes an iterator which replaces each run of whitespaces with the canonical form.
+    ///
+    /// A run containing no newlines is replaced with a single space, and
+    /// the other runs are replaced with the same number of newlines as the original.
+    ///
+    /// The positions of the resulting tokens reflect the normalized output
+    /// (i.e., they are located as if the tokens were tokenized from [`render`](crate::render)ed text).
+    ///
+    /// Note that the iteration stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{render, Tokenizer};
+    ///
+    /// let tokens = Tokenizer::new("foo  (1,\t\n\n    2)")
+    ///     .normalize_whitespace()
+    ///     .collect::<Result<Vec<_>, _>>()
+    ///     .unwrap();
+    /// assert_eq!(render(&tokens), "foo (1,\n\n2)");
+    /// ```
+    pub fn normalize_whitespace(self) -> impl Iterator<Item = Result<Token>> {
+        NormalizeWhitespace {
+            next_pos: self.next_position(),
+            inner: self,
+            pending: None,
+            queue: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Makes an iterator over the attribute forms (e.g., `-module(foo).`).
+    ///
+    /// An attribute form is a form starting with `-` followed by an atom or a keyword
+    /// (e.g., `-if(..)` and `-else.`).
+    /// Each item is a pair of the attribute name and the lexical tokens between the name and
+    /// the terminating dot (whitespaces and comments are excluded).
+    /// The other forms are skipped.
+    ///
+    /// Note that the iteration stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let src = "-module(foo).\nfoo() -> ok.\n-vsn(1).";
+    /// let attrs = Tokenizer::new(src).attributes().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(attrs.len(), 2);
+    /// assert_eq!(attrs[0].0.value(), "module");
+    /// assert_eq!(attrs[0].1.iter().map(|t| t.text()).collect::<Vec<_>>(), ["(", "foo", ")"]);
+    /// assert_eq!(attrs[1].0.value(), "vsn");
+    /// ```
+    pub fn attributes(self) -> impl Iterator<Item = Result<(AtomToken, Vec<Token>)>> {
+        Attributes {
+            inner: self,
+            done: false,
+        }
+    }
+
+    /// Makes an iterator over the documentation attributes (i.e., `-doc` and `-moduledoc`).
+    ///
+    /// Each item is a pair of the attribute name and the string token following it
+    /// (optionally enclosed in parentheses as in `-doc("...").`).
+    /// Whitespaces and comments between them are skipped.
+    /// Documentation attributes which have other values (e.g., `-doc false.` or a sigil string)
+    /// and the other forms are skipped.
+    ///
+    /// Note that the iteration stops after the first tokenization error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let src = "-moduledoc \"A module.\".\n-doc \"\"\"\n  Hello\n  \"\"\".\nfoo() -> ok.";
+    /// let docs = Tokenizer::new(src).doc_attributes().collect::<Result<Vec<_>, _>>().unwrap();
+    /// assert_eq!(docs.len(), 2);
+    /// assert_eq!(docs[0].0.value(), "moduledoc");
+    /// assert_eq!(docs[0].1.value(), "A module.");
+    /// assert_eq!(docs[1].0.value(), "doc");
+    /// assert_eq!(docs[1].1.value(), "Hello");
+    /// ```
+    pub fn doc_attributes(self) -> impl Iterator<Item = Result<(AtomToken, StringToken)>> {
+        self.attributes().filter_map(|attr| {
+            let (name, args) = match attr {
+                Err(e) => return Some(Err(e)),
+                Ok(attr) => attr,
+            };
+            if !matches!(name.value(), "doc" | "moduledoc") {
+                return None;
+            }
+            let mut args = args.into_iter().peekable();
+            let parenthesized = args
+                .next_if(|t| matches!(t, Token::Symbol(t) if t.value() == Symbol::OpenParen))
+                .is_some();
+            let doc = args.next()?.into_string_token().ok()?;
+            if parenthesized {
+                args.next()
+                    .filter(|t| matches!(t, Token::Symbol(t) if t.value() == Symbol::CloseParen))?;
+            }
+            if args.next().is_some() {
+                return None;
+            }
+            Some(Ok((name, doc)))
+        })
+    }
+}
+impl<'a> Tokenizer<&'a str> {
+    /// Makes a new `Tokenizer` instance which tokenizes the UTF-8 encoded bytes.
+    ///
+    /// If `bytes` is not valid UTF-8, this returns `Error::InvalidUtf8`
+    /// whose position points to the first invalid byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// assert_eq!(Tokenizer::from_bytes(b"foo bar").unwrap().count(), 3);
+    ///
+    /// let error = Tokenizer::from_bytes(b"foo\n\xFF").unwrap_err();
+    /// assert_eq!(error.position().line(), 2);
+    /// assert_eq!(error.position().column(), 1);
+    /// ```
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self> {
+        match str::from_utf8(bytes) {
+            Ok(text) => Ok(Tokenizer::new(text)),
+            Err(e) => {
+                let valid = unsafe { str::from_utf8_unchecked(&bytes[..e.valid_up_to()]) };
+                Err(Error::invalid_utf8(Position::new().step_by_text(valid)))
+            }
+        }
+    }
+
+    /// Tokenizes the whole text and returns the resulting tokens.
+    ///
+    /// If any error occurs, this function returns the first one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let src = r#"io:format("Hello")."#;
+    /// let tokens = Tokenizer::tokenize(src).unwrap();
+    ///
+    /// assert_eq!(tokens.iter().map(|t| t.text()).collect::<Vec<_>>(),
+    ///            ["io", ":", "format", "(", r#""Hello""#, ")", "."]);
+    /// ```
+    pub fn tokenize(text: &'a str) -> Result<Vec<Token>> {
+        Tokenizer::new(text).collect()
+    }
+
+    /// Tokenizes the text up to the end of `range` and returns the tokens overlapping the byte range.
+    ///
+    /// The text is scanned from the beginning, so strings, quoted atoms and comments
+    /// preceding `range` are always handled correctly (even if they contain a `.` followed by a newline).
+    /// Thus the cost is proportional to `range.end`, not to the length of `range`.
+    /// The scan stops at the first token starting after `range`, so the rest of the text is not tokenized.
+    /// Use [`Tokenizer::tokens_in_range_from`] to start the scan at a known token boundary.
+    ///
+    /// If `range` is empty, the token containing `range.start` (if any) is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{PositionRange, Tokenizer};
+    ///
+    /// let src = "foo() -> 1.\nbar() -> 2.\n";
+    /// let tokens = Tokenizer::tokens_in_range(src, 14..21).unwrap();
+    /// assert_eq!(tokens.iter().map(|t| t.text()).collect::<Vec<_>>(),
+    ///            ["bar", "(", ")", " ", "->", " "]);
+    /// assert_eq!(tokens[0].start_position().line(), 2);
+    /// ```
+    pub fn tokens_in_range(text: &'a str, range: Range<usize>) -> Result<Vec<Token>> {
+        Self::tokens_in_range_from(text, Position::new(), range)
+    }
+
+    /// Same as [`Tokenizer::tokens_in_range`] except that the scan starts at `start`.
+    ///
+    /// `start` must be the position of the beginning of a token which precedes `range`
+    /// (e.g., the end position of a previously tokenized token, or
+    /// [`Tokenizer::next_position`] after reading a form).
+    /// The tokens before `start` are not scanned, so the cost is proportional to
+    /// `range.end - start.offset()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let src = "foo() -> 1.\nbar() -> 2.\n";
+    /// let mut tokenizer = Tokenizer::new(src);
+    /// tokenizer.next_form();
+    /// let start = tokenizer.next_position();
+    /// let tokens = Tokenizer::tokens_in_range_from(src, start, 14..21).unwrap();
+    /// assert_eq!(tokens.iter().map(|t| t.text()).collect::<Vec<_>>(),
+    ///            ["bar", "(", ")", " ", "->", " "]);
+    /// ```
+    pub fn tokens_in_range_from(
+        text: &'a str,
+        start: Position,
+        range: Range<usize>,
+    ) -> Result<Vec<Token>> {
+        let mut tokenizer = Tokenizer::new(text);
+        tokenizer.set_position(start);
+
+        let mut tokens = Vec::new();
+        for token in tokenizer {
+            let token = token?;
+            if token.end_position().offset() <= range.start {
+                continue;
+            }
+            if token.start_position().offset() >= range.end && !tokens.is_empty() {
+                break;
+            }
+            tokens.push(token);
+            if range.is_empty() {
+                break;
+            }
+        }
+        Ok(tokens)
+    }
+}
+impl<T> Iterator for Tokenizer<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<Token>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            None
+        } else if self.text_offset() >= self.text.as_ref().len() {
+            if self.options.eof && !self.eof_emitted {
+                self.eof_emitted = true;
+                Some(Ok(Token::from(EofToken::new(self.next_pos.clone()))))
+            } else {
+                None
+            }
+        } else {
+            let text = unsafe {
+                self.text
+                    .as_ref()
+                    .get_unchecked(self.text_offset()..self.text.as_ref().len())
+            };
+            let cur_pos = self.next_pos.clone();
+            let limit = self.options.max_token_bytes;
+            let (text, truncated) = match limit {
+                Some(limit) => truncate_for_limit(text, limit),
+                None => (text, false),
+            };
+            let result =
+                if self.options.escript && self.text_offset() == 0 && text.starts_with("#!") {
+                    CommentToken::from_shebang_text(text, cur_pos.clone()).map(Token::from)
+                } else {
+                    Token::from_text_with_options(text, cur_pos.clone(), &self.options)
+                };
+            let result = match (result, limit) {
+                (Ok(t), Some(limit)) if t.text().len() > limit => {
+                    Err(Error::token_too_long(cur_pos, limit))
+                }
+                (Err(e), Some(limit))
+                    if truncated
+                        && (matches!(e, Error::NoClosingQuotation { .. })
+                            || e.position().offset() >= cur_pos.offset() + text.len()) =>
+                {
+                    Err(Error::token_too_long(cur_pos, limit))
+                }
+                (result, _) => result,
+            };
+            match result {
+                Err(e) => {
+                    self.failed = self.options.strict;
+                    Some(Err(e))
+                }
+                Ok(t) => {
+                    self.next_pos = t.end_position();
+                    Some(Ok(t))
+                }
+            }
+        }
+    }
+}
+
+/// Number of characters scanned beyond `max_token_bytes`.
+///
+/// This is enough to decide where a token within the limit ends
+/// (e.g., `1.0` followed by `e+5`, or `=:` followed by `=`).
+const TOKEN_LOOKAHEAD_CHARS: usize = 4;
+
+fn truncate_for_limit(text: &str, limit: usize) -> (&str, bool) {
+    let mut end = limit.min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    match text[end..].char_indices().nth(TOKEN_LOOKAHEAD_CHARS) {
+        Some((i, _)) => (&text[..end + i], true),
+        None => (text, false),
+    }
+}
+
+/// Lightweight cursor which borrows the text of a [`Tokenizer`].
+///
+/// This is an iterator which yields the same tokens as the tokenizer it was made from
+/// (see [`Tokenizer::cursor`]).
+/// Cloning a cursor is cheap because it does not copy the text.
+#[derive(Debug)]
+pub struct Cursor<'a> {
+    inner: Tokenizer<&'a str>,
+}
+impl Cursor<'_> {
+    /// Returns the position from which this cursor will start to scan the next token.
+    pub fn position(&self) -> Position {
+        self.inner.next_position()
+    }
+
+    /// Returns the next token without advancing this cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Tokenizer;
+    ///
+    /// let tokenizer = Tokenizer::new("foo bar");
+    /// let mut cursor = tokenizer.cursor();
+    /// assert_eq!(cursor.peek().unwrap().unwrap().text(), "foo");
+    /// assert_eq!(cursor.next().unwrap().unwrap().text(), "foo");
+    /// ```
+    pub fn peek(&self) -> Option<Result<Token>> {
+        self.clone().next()
+    }
+}
+impl Clone for Cursor<'_> {
+    fn clone(&self) -> Self {
+        Cursor {
+            inner: Tokenizer {
+                text: self.inner.text,
+                next_pos: self.inner.next_pos.clone(),
+                options: self.inner.options.clone(),
+                failed: self.inner.failed,
+                eof_emitted: self.inner.eof_emitted,
+            },
+        }
+    }
+}
+impl Iterator for Cursor<'_> {
+    type Item = Result<Token>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+/// Returns `true` if the whole text can be tokenized without errors, otherwise `false`.
+///
+/// This stops at the first error and does not collect the resulting tokens.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::is_valid;
+///
+/// assert!(is_valid("foo(Bar) -> [1, \"baz\"]."));
+/// assert!(!is_valid("foo(\"bar"));
+/// ```
+pub fn is_valid(text: &str) -> bool {
+    Tokenizer::new(text).all(|t| t.is_ok())
+}
+
+/// Returns the number of the tokens in the text.
+///
+/// The text is fully scanned and all the tokens including hidden ones are counted,
+/// so the result equals the length of `Tokenizer::tokenize(text)` (without allocating the tokens).
+/// If a tokenization error occurs, this function returns it immediately instead of
+/// counting the tokens before it.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::token_count;
+///
+/// assert_eq!(token_count("foo(Bar). % baz").unwrap(), 7);
+/// assert!(token_count("foo(\"bar").is_err());
+/// ```
+pub fn token_count(text: &str) -> Result<usize> {
+    let mut count = 0;
+    for token in Tokenizer::new(text) {
+        token?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Returns the start and end positions of all the tokens of the given kind in the text.
+///
+/// If a tokenization error occurs, this function returns it immediately.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{positions_of, TokenKind};
+///
+/// let ranges = positions_of(r#"foo("bar", "baz")"#, TokenKind::String).unwrap();
+/// assert_eq!(ranges.iter().map(|r| r.start.offset()..r.end.offset()).collect::<Vec<_>>(),
+///            [4..9, 11..16]);
+/// ```
+pub fn positions_of(text: &str, kind: TokenKind) -> Result<Vec<Range<Position>>> {
+    let mut ranges = Vec::new();
+    for token in Tokenizer::new(text) {
+        let token = token?;
+        if token.kind() == kind {
+            ranges.push(token.start_position()..token.end_position());
+        }
+    }
+    Ok(ranges)
+}
+
+/// Returns the decoded values and the start positions of all the atoms in the text.
+///
+/// Keywords (e.g., `case`) are not atoms, so they are excluded.
+/// If a tokenization error occurs, this function returns it immediately.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::atom_names;
+///
+/// let atoms = atom_names("foo:'bar baz'(case)").unwrap();
+/// assert_eq!(atoms.iter().map(|(name, pos)| (name.as_str(), pos.offset())).collect::<Vec<_>>(),
+///            [("foo", 0), ("bar baz", 4)]);
+/// ```
+pub fn atom_names(text: &str) -> Result<Vec<(String, Position)>> {
+    let mut atoms = Vec::new();
+    for token in Tokenizer::new(text) {
+        if let Token::Atom(t) = token? {
+            atoms.push((String::from(t.value()), t.start_position()));
+        }
+    }
+    Ok(atoms)
+}
+
+/// Same as [`atom_names`] except that only the first occurrence of each atom is returned.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::unique_atom_names;
+///
+/// let atoms = unique_atom_names("foo(bar, foo, 'bar')").unwrap();
+/// assert_eq!(atoms.iter().map(|(name, pos)| (name.as_str(), pos.offset())).collect::<Vec<_>>(),
+///            [("foo", 0), ("bar", 4)]);
+/// ```
+pub fn unique_atom_names(text: &str) -> Result<Vec<(String, Position)>> {
+    let mut seen = BTreeSet::new();
+    let mut atoms = atom_names(text)?;
+    atoms.retain(|(name, _)| seen.insert(name.clone()));
+    Ok(atoms)
+}
+
+/// Returns the byte ranges of the forms in the text.
+///
+/// Each range corresponds to a form returned by [`Tokenizer::next_form`],
+/// so the ranges are contiguous and cover the whole text
+/// (i.e., hidden tokens between forms belong to the following form, and
+/// the trailing tokens after the last terminator dot make up the last range).
+/// A range never splits a token such as a string, a comment or a quoted atom.
+/// Thus each range can be tokenized independently (e.g., by `Tokenizer::new(&text[range])`).
+///
+/// If a tokenization error occurs, this function returns it immediately.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::form_byte_ranges;
+///
+/// let text = "foo() -> \"a. b\".\nbar() -> ok.\n";
+/// let ranges = form_byte_ranges(text).unwrap();
+/// assert_eq!(ranges.iter().map(|r| &text[r.clone()]).collect::<Vec<_>>(),
+///            ["foo() -> \"a. b\".", "\nbar() -> ok.", "\n"]);
+/// ```
+pub fn form_byte_ranges(text: &str) -> Result<Vec<Range<usize>>> {
+    let mut ranges = Vec::new();
+    let mut tokenizer = Tokenizer::new(text);
+    let mut start = 0;
+    while let Some(form) = tokenizer.next_form() {
+        form?;
+        let end = tokenizer.next_position().offset();
+        ranges.push(start..end);
+        start = end;
+    }
+    Ok(ranges)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TokenizerOptions {
+    pub atom_head_char: fn(char) -> bool,
+    pub strict: bool,
+    pub escript: bool,
+    pub comment_prefix: char,
+    pub eof: bool,
+    pub maybe_feature: bool,
+    pub ascii_whitespace_only: bool,
+    pub forbid_multiline_strings: bool,
+    pub max_token_bytes: Option<usize>,
+    pub base: (usize, usize, usize),
+}
+impl TokenizerOptions {
+    pub fn is_keyword_enabled(&self, keyword: Keyword) -> bool {
+        self.maybe_feature || !matches!(keyword, Keyword::Maybe | Keyword::Else)
+    }
+}
+impl Default for TokenizerOptions {
+    fn default() -> Self {
+        TokenizerOptions {
+            atom_head_char: util::is_atom_head_char,
+            strict: false,
+            escript: false,
+            comment_prefix: '%',
+            eof: false,
+            maybe_feature: true,
+            ascii_whitespace_only: false,
+            forbid_multiline_strings: false,
+            max_token_bytes: None,
+            base: (0, 1, 1),
+        }
+    }
+}
+
+/// Text replacement used for [`Tokenizer::retokenize`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edit {
+    range: Range<usize>,
+    text: String,
+}
+impl Edit {
+    /// Makes a new `Edit` instance which replaces the byte range `range` of the old text with `text`.
+    pub fn new<S: Into<String>>(range: Range<usize>, text: S) -> Self {
+        Edit {
+            range,
+            text: text.into(),
+        }
+    }
+
+    /// Returns the replaced byte range of the old text.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the replacement text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Debug)]
+struct FoldSigns<T> {
+    inner: Tokenizer<T>,
+    pending: Option<Result<Token>>,
+    prefix_position: bool,
+    done: bool,
+}
+impl<T> Iterator for FoldSigns<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<Token>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let token = match self.pending.take().or_else(|| self.inner.next())? {
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+            Ok(t) => t,
+        };
+        if token.is_hidden_token() {
+            return Some(Ok(token));
+        }
+
+        let token = match token {
+            Token::Symbol(ref sign)
+                if self.prefix_position
+                    && matches!(sign.value(), Symbol::Hyphen | Symbol::Plus) =>
+            {
+                match self.inner.next() {
+                    Some(Ok(Token::Integer(t))) => Token::from(t.with_sign(sign)),
+                    Some(Ok(Token::Float(t))) => Token::from(t.with_sign(sign)),
+                    next => {
+                        self.pending = next;
+                        token
+                    }
+                }
+            }
+            _ => token,
+        };
+        self.prefix_position = match token {
+            Token::Symbol(ref t) => !matches!(
+                t.value(),
+                Symbol::CloseParen
+                    | Symbol::CloseSquare
+                    | Symbol::CloseBrace
+                    | Symbol::DoubleRightAngle
+            ),
+            Token::Keyword(ref t) => t.value() != Keyword::End,
+            _ => false,
+        };
+        Some(Ok(token))
+    }
+}
+
+#[derive(Debug)]
+struct ByLine<T> {
+    inner: Tokenizer<T>,
+    pending: Option<Result<Token>>,
+    done: bool,
+}
+impl<T> Iterator for ByLine<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<(usize, Vec<Token>)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let first = match self.pending.take().or_else(|| self.inner.next())? {
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+            Ok(t) => t,
+        };
+        let line = first.start_position().line();
+        let mut tokens = vec![first];
+        loop {
+            match self.inner.next() {
+                Some(Ok(token)) if token.start_position().line() == line => tokens.push(token),
+                next => {
+                    self.pending = next;
+                    break;
+                }
+            }
+        }
+        Some(Ok((line, tokens)))
+    }
+}
+
+#[derive(Debug)]
+struct CheckBrackets<T> {
+    inner: Tokenizer<T>,
+    stack: Vec<(Symbol, Position)>,
+    done: bool,
+}
+impl<T> Iterator for CheckBrackets<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<Token>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let token = match self.inner.next() {
+            None => {
+                self.done = true;
+                let (open, pos) = self.stack.pop()?;
+                return Some(Err(Error::unbalanced_bracket(pos, open)));
+            }
+            Some(Err(e)) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+            Some(Ok(t)) => t,
+        };
+        if let Token::Symbol(ref t) = token {
+            let symbol = t.value();
+            match symbol {
+                Symbol::OpenSquare
+                | Symbol::OpenParen
+                | Symbol::OpenBrace
+                | Symbol::DoubleLeftAngle => {
+                    self.stack.push((symbol, t.start_position()));
+                }
+                Symbol::CloseSquare
+                | Symbol::CloseParen
+                | Symbol::CloseBrace
+                | Symbol::DoubleRightAngle => {
+                    let open = self.stack.pop().map(|(open, _)| open);
+                    if open != symbol.matching() {
+                        self.done = true;
+                        return Some(Err(Error::unbalanced_bracket(t.start_position(), symbol)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(Ok(token))
+    }
+}
+
+#[derive(Debug)]
+struct WithTrivia<T> {
+    inner: Tokenizer<T>,
+    done: bool,
+}
+impl<T> Iterator for WithTrivia<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<(Vec<Token>, Token)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut trivia = Vec::new();
+        loop {
+            match self.inner.next() {
+                None => {
+                    self.done = true;
+                    let eof = EofToken::new(self.inner.next_position());
+                    return Some(Ok((trivia, Token::from(eof))));
+                }
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                Some(Ok(token)) => {
+                    if token.is_hidden_token() {
+                        trivia.push(token);
+                    } else {
+                        self.done = matches!(token, Token::Eof(_));
+                        return Some(Ok((trivia, token)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Gaps<T> {
+    inner: Tokenizer<T>,
+    done: bool,
+}
+impl<T> Iterator for Gaps<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<(Token, usize)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let mut gap = 0;
+        loop {
+            match self.inner.next()? {
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                Ok(Token::Whitespace(t)) => gap += t.text().len(),
+                Ok(Token::Comment(_)) => gap = 0,
+                Ok(Token::Eof(_)) => {}
+                Ok(token) => return Some(Ok((token, gap))),
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EnumerateLexical<T> {
+    inner: Tokenizer<T>,
+    index: usize,
+    done: bool,
+}
+impl<T> Iterator for EnumerateLexical<T>
+where
+    T: AsRef<str>,
+{
+    type Item = (usize, Result<Token>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        loop {
+            match self.inner.next()? {
+                Err(e) => {
+                    self.done = true;
+                    return Some((self.index, Err(e)));
+                }
+                Ok(token) if !token.is_lexical_token() => {}
+                Ok(token) => {
+                    self.index += 1;
+                    return Some((self.index - 1, Ok(token)));
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NormalizeWhitespace<T> {
+    inner: Tokenizer<T>,
+    next_pos: Position,
+    pending: Option<Result<Token>>,
+    queue: Vec<Token>,
+    done: bool,
+}
+impl<T> NormalizeWhitespace<T> {
+    fn relocate(&mut self, mut token: Token) -> Token {
+        token.set_position(self.next_pos.clone());
+        self.next_pos = token.end_position();
+        token
+    }
+}
+impl<T> Iterator for NormalizeWhitespace<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<Token>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(token) = self.queue.pop() {
+            return Some(Ok(token));
+        }
+        if self.done {
+            return None;
+        }
+
+        let token = match self.pending.take().or_else(|| self.inner.next())? {
+            Err(e) => {
+                self.done = true;
+                return Some(Err(e));
+            }
+            Ok(t) => t,
+        };
+        let Token::Whitespace(first) = token else {
+            return Some(Ok(self.relocate(token)));
+        };
+
+        let mut newlines = first.newline_count();
+        loop {
+            match self.inner.next() {
+                Some(Ok(Token::Whitespace(t))) => newlines += t.newline_count(),
+                next => {
+                    self.pending = next;
+                    break;
+                }
+            }
+        }
+
+        let (value, count) = if newlines == 0 {
+            (Whitespace::Space, 1)
+        } else {
+            (Whitespace::Newline, newlines)
+        };
+        for _ in 0..count {
+            let token = WhitespaceToken::from_value(value, Position::new());
+            let token = self.relocate(Token::from(token));
+            self.queue.push(token);
+        }
+        self.queue.reverse();
+        self.queue.pop().map(Ok)
+    }
+}
+
+#[derive(Debug)]
+struct Attributes<T> {
+    inner: Tokenizer<T>,
+    done: bool,
+}
+impl<T> Iterator for Attributes<T>
+where
+    T: AsRef<str>,
+{
+    type Item = Result<(AtomToken, Vec<Token>)>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let form = match self.inner.next_form()? {
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                Ok(form) => form,
+            };
+            let mut tokens = form.into_iter().filter(Token::is_lexical_token);
+            if !matches!(tokens.next(), Some(Token::Symbol(ref t)) if t.value() == Symbol::Hyphen) {
+                continue;
+            }
+            let name = match tokens.next() {
+                Some(Token::Atom(t)) => t,
+                // e.g., `-if(..)` and `-else.`
+                Some(Token::Keyword(t)) => {
+                    match AtomToken::from_text(t.text(), t.start_position()) {
+                        Ok(t) => t,
+                        Err(_) => continue,
+                    }
+                }
+                _ => continue,
+            };
+            let mut args = tokens.collect::<Vec<_>>();
+            if matches!(args.last(), Some(Token::Symbol(t)) if t.value() == Symbol::Dot) {
+                args.pop();
             }
+            return Some(Ok((name, args)));
         }
+        None
     }
 }
@@ -1,11 +1,16 @@
 //! Tokens.
-use num::{BigUint, Num};
-use std::borrow::Cow;
-use std::fmt;
-use std::str;
-
+use alloc::borrow::{Cow, ToOwned};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+use core::str;
+use num::{BigUint, Num, ToPrimitive};
+
+use crate::tokenizer::TokenizerOptions;
 use crate::util;
-use crate::values::{Keyword, Symbol, Whitespace};
+use crate::values::{Keyword, SigilKind, Symbol, Whitespace};
 use crate::{Error, Position, PositionRange, Result};
 
 /// Atom token.
@@ -44,10 +49,18 @@ impl AtomToken {
     /// use erl_tokenize::tokens::AtomToken;
     ///
     /// let pos = Position::new();
-    /// assert_eq!(AtomToken::from_value("foo", pos.clone()).text(), "'foo'");
-    /// assert_eq!(AtomToken::from_value("foo's", pos.clone()).text(), r"'foo\'s'");
+    /// assert_eq!(AtomToken::from_value("foo", pos.clone()).unwrap().text(), "'foo'");
+    /// assert_eq!(AtomToken::from_value("foo's", pos.clone()).unwrap().text(), r"'foo\'s'");
+    ///
+    /// // Control characters and too long values are rejected.
+    /// assert!(AtomToken::from_value("foo\0", pos.clone()).is_err());
+    /// assert!(AtomToken::from_value(&"a".repeat(256), pos.clone()).is_err());
     /// ```
-    pub fn from_value(value: &str, pos: Position) -> Self {
+    pub fn from_value(value: &str, pos: Position) -> Result<Self> {
+        if value.chars().any(char::is_control) || value.chars().count() > 255 {
+            return Err(Error::invalid_atom_token(pos));
+        }
+
         let mut text = "'".to_string();
         for c in value.chars() {
             match c {
@@ -57,15 +70,23 @@ impl AtomToken {
             }
         }
         text.push('\'');
-        AtomToken {
+        Ok(AtomToken {
             value: Some(value.to_string()),
             text,
             pos,
-        }
+        })
     }
 
     /// Tries to convert from any prefixes of the input text to an `AtomToken`.
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
+        Self::from_text_with_options(text, pos, &TokenizerOptions::default())
+    }
+
+    pub(crate) fn from_text_with_options(
+        text: &str,
+        pos: Position,
+        options: &TokenizerOptions,
+    ) -> Result<Self> {
         let head_len = text
             .chars()
             .next()
@@ -78,7 +99,7 @@ impl AtomToken {
             (value, unsafe { text.get_unchecked(0..=1 + end) })
         } else {
             let head = head.chars().next().expect("unreachable");
-            if !util::is_atom_head_char(head) {
+            if !(options.atom_head_char)(head) {
                 return Err(Error::invalid_atom_token(pos));
             }
             let end = head.len_utf8()
@@ -127,6 +148,44 @@ impl AtomToken {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns the byte range of the content (i.e., the text excluding the quotation marks)
+    /// relative to the start of this token.
+    ///
+    /// If this atom is not quoted, the range is the whole text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::AtomToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// assert_eq!(AtomToken::from_text("foo", pos.clone()).unwrap().content_range(), 0..3);
+    /// assert_eq!(AtomToken::from_text("'foo'", pos.clone()).unwrap().content_range(), 1..4);
+    /// ```
+    pub fn content_range(&self) -> Range<usize> {
+        if self.text.starts_with('\'') {
+            1..self.text.len() - 1
+        } else {
+            0..self.text.len()
+        }
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for AtomToken {
     fn start_position(&self) -> Position {
@@ -187,13 +246,17 @@ impl CharToken {
     ///
     /// let pos = Position::new();
     /// assert_eq!(CharToken::from_value('a', pos.clone()).text(), "$a");
+    /// assert_eq!(CharToken::from_value('\n', pos.clone()).text(), r"$\n");
+    /// assert_eq!(CharToken::from_value(' ', pos.clone()).text(), r"$\s");
+    /// assert_eq!(CharToken::from_value('\u{1}', pos.clone()).text(), r"$\x01");
     /// ```
     pub fn from_value(value: char, pos: Position) -> Self {
-        let text = if value == '\\' {
-            r"$\\".to_string()
+        let mut text = "$".to_string();
+        if value == ' ' {
+            text.push_str(r"\s");
         } else {
-            format!("${}", value)
-        };
+            util::push_escaped_char(&mut text, value, None);
+        }
         CharToken { value, text, pos }
     }
 
@@ -209,7 +272,7 @@ impl CharToken {
             .ok_or_else(|| Error::invalid_char_token(pos.clone()))?;
         let (value, end) = if c == '\\' {
             let mut chars = chars.peekable();
-            let value = util::parse_escaped_char(pos.clone(), &mut chars)?;
+            let value = util::parse_escaped_char(pos.clone() + 1, &mut chars)?;
             let end = chars.next().map(|(i, _)| i).unwrap_or_else(|| text.len());
             (value, end)
         } else {
@@ -254,6 +317,38 @@ impl CharToken {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns the byte range of the content (i.e., the text following `$`)
+    /// relative to the start of this token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::CharToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// assert_eq!(CharToken::from_text("$a", pos.clone()).unwrap().content_range(), 1..2);
+    /// assert_eq!(CharToken::from_text(r"$\123", pos.clone()).unwrap().content_range(), 1..5);
+    /// ```
+    pub fn content_range(&self) -> Range<usize> {
+        1..self.text.len()
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for CharToken {
     fn start_position(&self) -> Position {
@@ -290,6 +385,10 @@ impl fmt::Display for CharToken {
 pub struct CommentToken {
     text: String,
     pos: Position,
+    #[serde(default)]
+    terminated_by_newline: bool,
+    #[serde(default)]
+    shebang: bool,
 }
 impl CommentToken {
     /// Makes a new `CommentToken` instance from the value.
@@ -309,18 +408,49 @@ impl CommentToken {
         }
 
         let text = format!("%{}", value);
-        Ok(CommentToken { text, pos })
+        Ok(CommentToken {
+            text,
+            pos,
+            terminated_by_newline: false,
+            shebang: false,
+        })
     }
 
     /// Tries to convert from any prefixes of the text to a `CommentToken`.
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
-        if !text.starts_with('%') {
+        Self::from_text_with_prefix(text, pos, '%')
+    }
+
+    pub(crate) fn from_text_with_prefix(text: &str, pos: Position, prefix: char) -> Result<Self> {
+        if !text.starts_with(prefix) {
+            return Err(Error::invalid_comment_token(pos));
+        }
+
+        let end = text.find('\n').unwrap_or(text.len());
+        let terminated_by_newline = end < text.len();
+        let text = unsafe { text.get_unchecked(0..end) }.to_owned();
+        Ok(CommentToken {
+            text,
+            pos,
+            terminated_by_newline,
+            shebang: false,
+        })
+    }
+
+    pub(crate) fn from_shebang_text(text: &str, pos: Position) -> Result<Self> {
+        if !text.starts_with("#!") {
             return Err(Error::invalid_comment_token(pos));
         }
 
         let end = text.find('\n').unwrap_or(text.len());
+        let terminated_by_newline = end < text.len();
         let text = unsafe { text.get_unchecked(0..end) }.to_owned();
-        Ok(CommentToken { text, pos })
+        Ok(CommentToken {
+            text,
+            pos,
+            terminated_by_newline,
+            shebang: true,
+        })
     }
 
     /// Returns the value of this token.
@@ -336,8 +466,42 @@ impl CommentToken {
     /// assert_eq!(CommentToken::from_text("%", pos.clone()).unwrap().value(), "");
     /// assert_eq!(CommentToken::from_text("%% foo ", pos.clone()).unwrap().value(), "% foo ");
     /// ```
+    ///
+    /// For a shebang line, the value is the text following `#!`.
     pub fn value(&self) -> &str {
-        unsafe { self.text().get_unchecked(1..self.text.len()) }
+        let prefix_len = if self.shebang {
+            2
+        } else {
+            self.text.chars().next().map_or(0, char::len_utf8)
+        };
+        unsafe { self.text().get_unchecked(prefix_len..self.text.len()) }
+    }
+
+    /// Returns `true` if this comment was followed by a newline in the source text,
+    /// otherwise `false` (i.e., the comment is located at the end of the text).
+    ///
+    /// For comments made by [`CommentToken::from_value`], this method always returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::CommentToken;
+    ///
+    /// let pos = Position::new();
+    /// assert!(CommentToken::from_text("% foo\nbar", pos.clone()).unwrap().terminated_by_newline());
+    /// assert!(!CommentToken::from_text("% foo", pos.clone()).unwrap().terminated_by_newline());
+    /// ```
+    pub fn terminated_by_newline(&self) -> bool {
+        self.terminated_by_newline
+    }
+
+    /// Returns `true` if this token is an escript shebang line (e.g., `#!/usr/bin/env escript`).
+    ///
+    /// Such tokens are only produced by a tokenizer in the escript mode
+    /// (see [`Tokenizer::escript_mode`](crate::Tokenizer::escript_mode)).
+    pub fn is_shebang(&self) -> bool {
+        self.shebang
     }
 
     /// Returns the original textual representation of this token.
@@ -356,6 +520,20 @@ impl CommentToken {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for CommentToken {
     fn start_position(&self) -> Position {
@@ -371,6 +549,66 @@ impl fmt::Display for CommentToken {
     }
 }
 
+/// End-of-input token.
+///
+/// This is a zero-width sentinel token which is only produced by a tokenizer with
+/// [`Tokenizer::with_eof_token`](crate::Tokenizer::with_eof_token) enabled.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{Position, PositionRange};
+/// use erl_tokenize::tokens::EofToken;
+///
+/// let pos = Position::new();
+/// let token = EofToken::new(pos.clone());
+/// assert_eq!(token.text(), "");
+/// assert_eq!(token.start_position(), pos);
+/// assert_eq!(token.end_position(), pos);
+/// ```
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EofToken {
+    pos: Position,
+}
+impl EofToken {
+    /// Makes a new `EofToken` instance located at `pos`.
+    pub fn new(pos: Position) -> Self {
+        EofToken { pos }
+    }
+
+    /// Returns the original textual representation of this token (i.e., the empty string).
+    pub fn text(&self) -> &'static str {
+        ""
+    }
+
+    /// Returns the length of the text of this token in bytes (i.e., `0`).
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token (i.e., `0`).
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
+}
+impl PositionRange for EofToken {
+    fn start_position(&self) -> Position {
+        self.pos.clone()
+    }
+    fn end_position(&self) -> Position {
+        self.pos.clone()
+    }
+}
+impl fmt::Display for EofToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.text().fmt(f)
+    }
+}
+
 /// Floating point number token.
 ///
 /// # Examples
@@ -406,6 +644,9 @@ pub struct FloatToken {
 impl FloatToken {
     /// Makes a new `FloatToken` instance from the value.
     ///
+    /// Like Erlang, float tokens are unsigned (a leading `-` is a separate symbol token),
+    /// so negative values (including `-0.0`) are rejected in addition to NaN and infinities.
+    ///
     /// # Examples
     ///
     /// ```
@@ -413,18 +654,47 @@ impl FloatToken {
     /// use erl_tokenize::tokens::FloatToken;
     ///
     /// let pos = Position::new();
-    /// assert_eq!(FloatToken::from_value(1.23, pos.clone()).text(), "1.23");
+    /// assert_eq!(FloatToken::from_value(1.23, pos.clone()).unwrap().text(), "1.23");
+    /// assert_eq!(FloatToken::from_value(1.0, pos.clone()).unwrap().text(), "1.0");
+    ///
+    /// // NaN and infinities cannot be represented in Erlang source code.
+    /// assert!(FloatToken::from_value(f64::NAN, pos.clone()).is_err());
+    /// assert!(FloatToken::from_value(f64::INFINITY, pos.clone()).is_err());
+    ///
+    /// // Negative values are not tokens by themselves.
+    /// assert!(FloatToken::from_value(-1.5, pos.clone()).is_err());
     /// ```
-    pub fn from_value(value: f64, pos: Position) -> Self {
-        let text = format!("{}", value);
-        FloatToken { value, text, pos }
+    pub fn from_value(value: f64, pos: Position) -> Result<Self> {
+        if !value.is_finite() || value.is_sign_negative() {
+            return Err(Error::invalid_float_token(pos));
+        }
+
+        let text = util::format_float(value);
+        Ok(FloatToken { value, text, pos })
     }
 
     /// Tries to convert from any prefixes of the text to a `FloatToken`.
+    ///
+    /// If a digit is missing (e.g., before or after the decimal point),
+    /// this returns `Error::ExpectedDigit` located where the digit is expected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Error, Position};
+    /// use erl_tokenize::tokens::FloatToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// let error = FloatToken::from_text("1.", pos.clone()).unwrap_err();
+    /// assert!(matches!(error, Error::ExpectedDigit { .. }));
+    /// assert_eq!(error.position().offset(), 2);
+    /// ```
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
         fn read_digits(
             buf: &mut String,
-            chars: &mut std::iter::Peekable<impl Iterator<Item = (usize, char)>>,
+            chars: &mut core::iter::Peekable<impl Iterator<Item = (usize, char)>>,
+            text: &str,
             pos: &Position,
         ) -> Result<()> {
             let mut needs_digit = true;
@@ -441,7 +711,8 @@ impl FloatToken {
                 let _ = chars.next();
             }
             if needs_digit {
-                Err(Error::invalid_float_token(pos.clone()))
+                let offset = chars.peek().map_or(text.len(), |&(i, _)| i);
+                Err(Error::expected_digit(pos.clone().step_by_width(offset)))
             } else {
                 Ok(())
             }
@@ -449,13 +720,13 @@ impl FloatToken {
 
         let mut chars = text.char_indices().peekable();
         let mut buf = String::new();
-        read_digits(&mut buf, &mut chars, &pos)?;
+        read_digits(&mut buf, &mut chars, text, &pos)?;
         if chars.next().map(|(_, c)| c) != Some('.') {
             return Err(Error::invalid_float_token(pos));
         }
         buf.push('.');
 
-        read_digits(&mut buf, &mut chars, &pos)?;
+        read_digits(&mut buf, &mut chars, text, &pos)?;
 
         if let Some((_, c @ ('e' | 'E'))) = chars.peek().cloned() {
             let _ = chars.next();
@@ -464,7 +735,7 @@ impl FloatToken {
                 let _ = chars.next();
                 buf.push(c);
             }
-            read_digits(&mut buf, &mut chars, &pos)?;
+            read_digits(&mut buf, &mut chars, text, &pos)?;
         }
 
         let end = chars.next().map(|(i, _)| i).unwrap_or_else(|| text.len());
@@ -508,6 +779,36 @@ impl FloatToken {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns `true` if this token has a minus sign, otherwise `false`.
+    ///
+    /// Float tokens are unsigned unless produced by [`Tokenizer::fold_signs`].
+    /// Note that [`FloatToken::value`] always returns the absolute value.
+    ///
+    /// [`Tokenizer::fold_signs`]: crate::Tokenizer::fold_signs
+    pub fn is_negative(&self) -> bool {
+        self.text.starts_with('-')
+    }
+
+    pub(crate) fn with_sign(mut self, sign: &SymbolToken) -> Self {
+        self.text.insert_str(0, sign.text());
+        self.pos = sign.start_position();
+        self
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for FloatToken {
     fn start_position(&self) -> Position {
@@ -576,6 +877,71 @@ impl IntegerToken {
         IntegerToken { value, text, pos }
     }
 
+    /// Makes a new `IntegerToken` instance from the value, which is rendered as `radix#digits`.
+    ///
+    /// The digits greater than `9` are rendered in lowercase.
+    /// If `radix` is not in the range `2..=36`, `Error::InvalidRadix` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::IntegerToken;
+    ///
+    /// let pos = Position::new();
+    /// let token = IntegerToken::from_value_with_radix(255u32.into(), 16, pos.clone()).unwrap();
+    /// assert_eq!(token.text(), "16#ff");
+    ///
+    /// assert!(IntegerToken::from_value_with_radix(255u32.into(), 37, pos.clone()).is_err());
+    /// ```
+    pub fn from_value_with_radix(value: BigUint, radix: u32, pos: Position) -> Result<Self> {
+        if !(2..=36).contains(&radix) {
+            return Err(Error::invalid_radix(pos, radix));
+        }
+        let text = format!("{}#{}", radix, value.to_str_radix(radix));
+        Ok(IntegerToken { value, text, pos })
+    }
+
+    /// Makes a new `IntegerToken` instance from the decimal digits `s`.
+    ///
+    /// Like integer literals in Erlang, a single `_` can be used as a separator between digits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::IntegerToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// // Ok
+    /// let token = IntegerToken::from_decimal_str("1_000", pos.clone()).unwrap();
+    /// assert_eq!(token.value().to_string(), "1000");
+    /// assert_eq!(token.text(), "1_000");
+    ///
+    /// // Err
+    /// assert!(IntegerToken::from_decimal_str("", pos.clone()).is_err());
+    /// assert!(IntegerToken::from_decimal_str("1__0", pos.clone()).is_err());
+    /// assert!(IntegerToken::from_decimal_str("16#FF", pos.clone()).is_err());
+    /// ```
+    pub fn from_decimal_str(s: &str, pos: Position) -> Result<Self> {
+        let is_valid = s
+            .split('_')
+            .all(|digits| !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()));
+        if !is_valid {
+            return Err(Error::invalid_integer_token(pos));
+        }
+
+        let digits = s.replace('_', "");
+        let value = Num::from_str_radix(&digits, 10)
+            .map_err(|_| Error::invalid_integer_token(pos.clone()))?;
+        Ok(IntegerToken {
+            value,
+            text: s.to_owned(),
+            pos,
+        })
+    }
+
     /// Tries to convert from any prefixes of the text to an `IntegerToken`.
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
         let mut has_radix = false;
@@ -589,7 +955,7 @@ impl IntegerToken {
                     .parse()
                     .map_err(|_| Error::invalid_integer_token(pos.clone()))?;
                 if !(1 < radix && radix < 37) {
-                    return Err(Error::invalid_integer_token(pos));
+                    return Err(Error::invalid_radix(pos, radix));
                 }
                 digits.clear();
                 needs_digit = true;
@@ -655,6 +1021,115 @@ impl IntegerToken {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns `true` if this token has a minus sign, otherwise `false`.
+    ///
+    /// Integer tokens are unsigned unless produced by [`Tokenizer::fold_signs`].
+    /// Note that [`IntegerToken::value`] always returns the absolute value.
+    ///
+    /// [`Tokenizer::fold_signs`]: crate::Tokenizer::fold_signs
+    pub fn is_negative(&self) -> bool {
+        self.text.starts_with('-')
+    }
+
+    /// Returns the character whose code point is the value of this token.
+    ///
+    /// If the value is negative or not a valid Unicode scalar value
+    /// (e.g., a surrogate or greater than `0x10FFFF`), this returns `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::IntegerToken;
+    ///
+    /// let pos = Position::new();
+    /// assert_eq!(IntegerToken::from_text("65", pos.clone()).unwrap().as_char(), Some('A'));
+    /// assert_eq!(IntegerToken::from_text("16#D800", pos.clone()).unwrap().as_char(), None);
+    /// ```
+    pub fn as_char(&self) -> Option<char> {
+        if self.is_negative() {
+            return None;
+        }
+        self.value.to_u32().and_then(char::from_u32)
+    }
+
+    /// Returns `true` if the digits of this token contain `_` separators, otherwise `false`.
+    ///
+    /// Only the digits following the radix prefix (see [`IntegerToken::digits`]) are considered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::IntegerToken;
+    ///
+    /// let pos = Position::new();
+    /// assert!(IntegerToken::from_text("1_000", pos.clone()).unwrap().has_separators());
+    /// assert!(IntegerToken::from_text("16#F_F", pos.clone()).unwrap().has_separators());
+    /// assert!(!IntegerToken::from_text("1000", pos.clone()).unwrap().has_separators());
+    /// ```
+    pub fn has_separators(&self) -> bool {
+        self.digits().contains('_')
+    }
+
+    /// Returns the lengths of the runs of digits separated by `_`.
+    ///
+    /// Only the digits following the radix prefix (see [`IntegerToken::digits`]) are considered.
+    /// If the token has no separators, the result consists of the number of the digits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::IntegerToken;
+    ///
+    /// let pos = Position::new();
+    /// assert_eq!(IntegerToken::from_text("1_000", pos.clone()).unwrap().grouping(), [1, 3]);
+    /// assert_eq!(IntegerToken::from_text("1000", pos.clone()).unwrap().grouping(), [4]);
+    /// ```
+    pub fn grouping(&self) -> Vec<usize> {
+        self.digits().split('_').map(str::len).collect()
+    }
+
+    /// Returns the digits of this token as written in the source text.
+    ///
+    /// The radix prefix (e.g., `16#`) and the sign are excluded, but the `_` separators are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::IntegerToken;
+    ///
+    /// let pos = Position::new();
+    /// assert_eq!(IntegerToken::from_text("16#aB0e", pos.clone()).unwrap().digits(), "aB0e");
+    /// assert_eq!(IntegerToken::from_text("10", pos.clone()).unwrap().digits(), "10");
+    /// ```
+    pub fn digits(&self) -> &str {
+        let text = self.text.trim_start_matches(['-', '+']);
+        text.rsplit('#').next().unwrap_or(text)
+    }
+
+    pub(crate) fn with_sign(mut self, sign: &SymbolToken) -> Self {
+        self.text.insert_str(0, sign.text());
+        self.pos = sign.start_position();
+        self
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for IntegerToken {
     fn start_position(&self) -> Position {
@@ -713,40 +1188,37 @@ impl KeywordToken {
     }
 
     /// Tries to convert from any prefixes of the text to a `KeywordToken`.
+    ///
+    /// If the prefix is a valid atom but not a keyword,
+    /// this returns `Error::UnknownKeyword` whose position is the start of the atom.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::{Error, Position};
+    /// use erl_tokenize::tokens::KeywordToken;
+    ///
+    /// let pos = Position::new();
+    /// assert!(KeywordToken::from_text("case ", pos.clone()).is_ok());
+    ///
+    /// match KeywordToken::from_text("foo ", pos.clone()) {
+    ///     Err(Error::UnknownKeyword { keyword, .. }) => assert_eq!(keyword, "foo"),
+    ///     _ => panic!(),
+    /// }
+    /// ```
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
-        let atom = AtomToken::from_text(text, pos.clone())?;
-        let value = match atom.text() {
-            "after" => Keyword::After,
-            "and" => Keyword::And,
-            "andalso" => Keyword::Andalso,
-            "band" => Keyword::Band,
-            "begin" => Keyword::Begin,
-            "bnot" => Keyword::Bnot,
-            "bor" => Keyword::Bor,
-            "bsl" => Keyword::Bsl,
-            "bsr" => Keyword::Bsr,
-            "bxor" => Keyword::Bxor,
-            "case" => Keyword::Case,
-            "catch" => Keyword::Catch,
-            "cond" => Keyword::Cond,
-            "div" => Keyword::Div,
-            "end" => Keyword::End,
-            "fun" => Keyword::Fun,
-            "if" => Keyword::If,
-            "let" => Keyword::Let,
-            "not" => Keyword::Not,
-            "of" => Keyword::Of,
-            "or" => Keyword::Or,
-            "orelse" => Keyword::Orelse,
-            "receive" => Keyword::Receive,
-            "rem" => Keyword::Rem,
-            "try" => Keyword::Try,
-            "when" => Keyword::When,
-            "xor" => Keyword::Xor,
-            "maybe" => Keyword::Maybe,
-            "else" => Keyword::Else,
-            s => return Err(Error::unknown_keyword(pos, s.to_owned())),
-        };
+        Self::from_text_with_options(text, pos, &TokenizerOptions::default())
+    }
+
+    pub(crate) fn from_text_with_options(
+        text: &str,
+        pos: Position,
+        options: &TokenizerOptions,
+    ) -> Result<Self> {
+        let atom = AtomToken::from_text_with_options(text, pos.clone(), options)?;
+        let value = Keyword::from_name(atom.text())
+            .filter(|&k| options.is_keyword_enabled(k))
+            .ok_or_else(|| Error::unknown_keyword(pos.clone(), atom.text().to_owned()))?;
         Ok(KeywordToken { value, pos })
     }
 
@@ -786,6 +1258,20 @@ impl KeywordToken {
     pub fn text(&self) -> &'static str {
         self.value.as_str()
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for KeywordToken {
     fn start_position(&self) -> Position {
@@ -853,6 +1339,95 @@ impl SigilStringToken {
         (&self.prefix, &self.content, &self.suffix)
     }
 
+    /// Returns the value of this token without processing escape sequences in the content.
+    ///
+    /// The content is the slice of the text specified by [`SigilStringToken::content_range`]
+    /// (i.e., the text between the delimiters or, for triple-quoted sigils, the lines between
+    /// the opening and closing lines without removing the indentation).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::SigilStringToken;
+    ///
+    /// # fn main() -> erl_tokenize::Result<()> {
+    /// let pos = Position::new();
+    ///
+    /// let token = SigilStringToken::from_text(r#"~b"a\tb""#, pos.clone())?;
+    /// assert_eq!(token.value(), ("b", "a\tb", ""));
+    /// assert_eq!(token.raw_value(), ("b", r"a\tb", ""));
+    ///
+    /// let token = SigilStringToken::from_text("~\"\"\"\n  a\n  \"\"\"", pos.clone())?;
+    /// assert_eq!(token.value(), ("", "a", ""));
+    /// assert_eq!(token.raw_value(), ("", "  a", ""));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raw_value(&self) -> (&str, &str, &str) {
+        (&self.prefix, &self.text[self.content_range()], &self.suffix)
+    }
+
+    /// Returns the kind of this sigil which is determined by the prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::SigilStringToken;
+    /// use erl_tokenize::values::SigilKind;
+    ///
+    /// # fn main() -> erl_tokenize::Result<()> {
+    /// let pos = Position::new();
+    ///
+    /// assert_eq!(SigilStringToken::from_text(r#"~b"x""#, pos.clone())?.sigil_kind(), SigilKind::Binary);
+    /// assert_eq!(SigilStringToken::from_text(r#"~S"x""#, pos.clone())?.sigil_kind(), SigilKind::Verbatim);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sigil_kind(&self) -> SigilKind<'_> {
+        SigilKind::from_prefix(&self.prefix)
+    }
+
+    /// Returns the content of this token with escape sequences processed according to the sigil.
+    ///
+    /// Unlike [`SigilStringToken::value`], the content of verbatim sigils (i.e., `~B` and `~S`)
+    /// is returned as it is (i.e., the same as [`SigilStringToken::raw_value`]).
+    /// The content of triple-quoted sigils is the same as [`SigilStringToken::value`]
+    /// (i.e., the indentation is removed and no escape sequences are processed).
+    /// For the other sigils (e.g., `~`, `~b` and `~s`), this method decodes escape sequences
+    /// in the same way as normal strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::SigilStringToken;
+    ///
+    /// # fn main() -> erl_tokenize::Result<()> {
+    /// let pos = Position::new();
+    ///
+    /// let token = SigilStringToken::from_text(r#"~"a\tb""#, pos.clone())?;
+    /// assert_eq!(token.decoded_content()?, "a\tb");
+    ///
+    /// let token = SigilStringToken::from_text(r#"~S"a\tb""#, pos.clone())?;
+    /// assert_eq!(token.value().1, "a\tb");
+    /// assert_eq!(token.decoded_content()?, r"a\tb");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn decoded_content(&self) -> Result<Cow<'_, str>> {
+        let delimited = &self.text[1 + self.prefix.len()..];
+        if delimited.starts_with(r#"""""#) {
+            return Ok(Cow::Borrowed(&self.content));
+        }
+        let (_, raw, _) = self.raw_value();
+        if self.sigil_kind() == SigilKind::Verbatim {
+            return Ok(Cow::Borrowed(raw));
+        }
+        util::unescape(self.pos.clone() + self.content_range().start, raw)
+    }
+
     /// Returns the original textual representation of this token.
     ///
     /// # Examples
@@ -874,6 +1449,45 @@ impl SigilStringToken {
         &self.text
     }
 
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns the byte range of the content (i.e., the text between the delimiters)
+    /// relative to the start of this token.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::SigilStringToken;
+    ///
+    /// # fn main() -> erl_tokenize::Result<()> {
+    /// let pos = Position::new();
+    ///
+    /// assert_eq!(SigilStringToken::from_text(r#"~"foo""#, pos.clone())?.content_range(), 2..5);
+    /// assert_eq!(SigilStringToken::from_text(r#"~b(foo)x"#, pos.clone())?.content_range(), 3..6);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn content_range(&self) -> Range<usize> {
+        let start = 1 + self.prefix.len();
+        let end = self.text.len() - self.suffix.len();
+        let delimited = &self.text[start..end];
+        if delimited.starts_with('"') {
+            let range = StringToken::content_range_of(delimited);
+            start + range.start..start + range.end
+        } else {
+            start + 1..end - 1
+        }
+    }
+
     /// Tries to convert from any prefixes of the text to a [`SigilStringToken`].
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
         if !text.starts_with('~') {
@@ -921,6 +1535,10 @@ impl SigilStringToken {
             pos,
         })
     }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 
 impl PositionRange for SigilStringToken {
@@ -974,9 +1592,10 @@ impl StringToken {
     ///
     /// let pos = Position::new();
     /// assert_eq!(StringToken::from_value("foo", pos.clone()).text(), r#""foo""#);
+    /// assert_eq!(StringToken::from_value("a\u{1b}\"b\"", pos.clone()).text(), r#""a\e\"b\"""#);
     /// ```
     pub fn from_value(value: &str, pos: Position) -> Self {
-        let text = format!("{:?}", value);
+        let text = util::quote(value, '"');
         StringToken {
             value: Some(value.to_string()),
             text,
@@ -1127,6 +1746,28 @@ impl StringToken {
         }
     }
 
+    /// Returns the content of this token without processing escape sequences.
+    ///
+    /// This is the slice of the text specified by [`StringToken::content_range`]
+    /// (i.e., the text between the quotation marks or, for triple-quoted strings, the lines between
+    /// the opening and closing lines without removing the indentation).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::StringToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// let token = StringToken::from_text(r#""b\tar""#, pos.clone()).unwrap();
+    /// assert_eq!(token.value(), "b\tar");
+    /// assert_eq!(token.raw_value(), r"b\tar");
+    /// ```
+    pub fn raw_value(&self) -> &str {
+        &self.text[self.content_range()]
+    }
+
     /// Returns the original textual representation of this token.
     ///
     /// # Examples
@@ -1147,6 +1788,122 @@ impl StringToken {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns the byte range of the content (i.e., the text excluding the quotation marks)
+    /// relative to the start of this token.
+    ///
+    /// For triple-quoted strings, the range covers the lines between the opening and closing lines
+    /// (the indentation is not removed).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::StringToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// assert_eq!(StringToken::from_text(r#""foo""#, pos.clone()).unwrap().content_range(), 1..4);
+    /// assert_eq!(StringToken::from_text("\"\"\"\nfoo\n\"\"\"", pos.clone()).unwrap().content_range(),
+    ///            4..7);
+    /// ```
+    pub fn content_range(&self) -> Range<usize> {
+        Self::content_range_of(&self.text)
+    }
+
+    fn content_range_of(text: &str) -> Range<usize> {
+        if text.starts_with(r#"""""#) {
+            let quote_count = text.bytes().take_while(|&b| b == b'"').count();
+            let start = text.find('\n').map_or(quote_count, |i| i + 1);
+            let end = text[..text.len() - quote_count]
+                .rfind('\n')
+                .map_or(start, |i| i.max(start));
+            start..end
+        } else {
+            1..text.len() - 1
+        }
+    }
+
+    /// Returns an iterator over the raw lines of the content of this token.
+    ///
+    /// For triple-quoted strings, the lines between the opening and closing lines are returned
+    /// without removing the indentation (see also [`StringToken::common_indent`]).
+    /// Otherwise, the content is split by newlines as it is (escape sequences are not processed).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::StringToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// let text = "\"\"\"\n  foo\n    bar\n  \"\"\"";
+    /// let token = StringToken::from_text(text, pos.clone()).unwrap();
+    /// assert_eq!(token.raw_lines().collect::<Vec<_>>(), ["  foo", "    bar"]);
+    ///
+    /// let token = StringToken::from_text("\"foo\nbar\"", pos.clone()).unwrap();
+    /// assert_eq!(token.raw_lines().collect::<Vec<_>>(), ["foo", "bar"]);
+    /// ```
+    pub fn raw_lines(&self) -> impl Iterator<Item = &str> {
+        let body = match self.triple_quoted_body() {
+            Some((body, _)) => body,
+            None => Some(&self.text[self.content_range()]),
+        };
+        body.unwrap_or_default()
+            .split('\n')
+            .skip(usize::from(body.is_none()))
+    }
+
+    /// Returns the indentation width which is removed from each line of a triple-quoted string
+    /// (i.e., the number of whitespace characters preceding the closing quotation marks).
+    ///
+    /// This method always returns `0` for strings that are not triple-quoted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::StringToken;
+    ///
+    /// let pos = Position::new();
+    ///
+    /// let text = "\"\"\"\n  foo\n    bar\n  \"\"\"";
+    /// assert_eq!(StringToken::from_text(text, pos.clone()).unwrap().common_indent(), 2);
+    /// assert_eq!(StringToken::from_text("\"  foo\"", pos.clone()).unwrap().common_indent(), 0);
+    /// ```
+    pub fn common_indent(&self) -> usize {
+        self.triple_quoted_body().map_or(0, |(_, indent)| indent)
+    }
+
+    // Returns the body (`None` if it has no lines) and the indentation width.
+    fn triple_quoted_body(&self) -> Option<(Option<&str>, usize)> {
+        if !self.text.starts_with(r#"""""#) {
+            return None;
+        }
+        let quote_count = self.text.bytes().take_while(|&b| b == b'"').count();
+        let start = self.text.find('\n')? + 1;
+        let end = self.text.rfind('\n')? + 1;
+        let indent = self.text[end..self.text.len() - quote_count]
+            .chars()
+            .count();
+        let body = (start < end).then(|| &self.text[start..end - 1]);
+        Some((body, indent))
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for StringToken {
     fn start_position(&self) -> Position {
@@ -1204,6 +1961,10 @@ impl SymbolToken {
     }
 
     /// Tries to convert from any prefixes of the text to a `SymbolToken`.
+    ///
+    /// The longest symbol at the beginning of the text is taken
+    /// (e.g., `-->` starts with `--`, not `-`, as in `erl_scan`).
+    /// If the text does not start with a symbol, `Error::UnexpectedChar` which has the leading char is returned.
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
         let bytes = text.as_bytes();
         let mut symbol = if bytes.len() >= 3 {
@@ -1211,6 +1972,8 @@ impl SymbolToken {
                 b"=:=" => Some(Symbol::ExactEq),
                 b"=/=" => Some(Symbol::ExactNotEq),
                 b"..." => Some(Symbol::TripleDot),
+                b"<:-" => Some(Symbol::StrictLeftArrow),
+                b"<:=" => Some(Symbol::StrictDoubleLeftArrow),
                 _ => None,
             }
         } else {
@@ -1267,6 +2030,8 @@ impl SymbolToken {
         }
         if let Some(value) = symbol {
             Ok(SymbolToken { value, pos })
+        } else if let Some(ch) = text.chars().next() {
+            Err(Error::unexpected_char(pos, ch))
         } else {
             Err(Error::invalid_symbol_token(pos))
         }
@@ -1306,13 +2071,38 @@ impl SymbolToken {
     pub fn text(&self) -> &'static str {
         self.value.as_str()
     }
+
+    /// Returns the length of the text of this token in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::SymbolToken;
+    /// use erl_tokenize::values::Symbol;
+    ///
+    /// let pos = Position::new();
+    /// assert_eq!(SymbolToken::from_value(Symbol::ExactEq, pos.clone()).byte_len(), 3);
+    /// ```
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for SymbolToken {
     fn start_position(&self) -> Position {
         self.pos.clone()
     }
     fn end_position(&self) -> Position {
-        self.pos.clone().step_by_width(self.text().len())
+        self.pos.clone().step_by_width(self.byte_len())
     }
 }
 impl fmt::Display for SymbolToken {
@@ -1323,6 +2113,11 @@ impl fmt::Display for SymbolToken {
 
 /// Variable token.
 ///
+/// A variable starts with an ASCII uppercase letter, `_` or a character having the Unicode
+/// `Uppercase` property (e.g., `Ä`), and the rest consists of alphanumeric characters, `_` and `@`.
+/// This generalizes `erl_scan`, which only accepts Latin-1 letters, in the same way as unquoted atoms
+/// (which start with a lowercase letter such as `ä`).
+///
 /// # Examples
 ///
 /// ```
@@ -1335,6 +2130,7 @@ impl fmt::Display for SymbolToken {
 /// assert_eq!(VariableToken::from_text("Foo", pos.clone()).unwrap().value(), "Foo");
 /// assert_eq!(VariableToken::from_text("_  ", pos.clone()).unwrap().value(), "_");
 /// assert_eq!(VariableToken::from_text("_foo@bar", pos.clone()).unwrap().value(), "_foo@bar");
+/// assert_eq!(VariableToken::from_text("Äpfel", pos.clone()).unwrap().value(), "Äpfel");
 ///
 /// // Err
 /// assert!(VariableToken::from_text("foo", pos.clone()).is_err());
@@ -1416,6 +2212,20 @@ impl VariableToken {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for VariableToken {
     fn start_position(&self) -> Position {
@@ -1473,16 +2283,7 @@ impl WhitespaceToken {
 
     /// Tries to convert from any prefixes of the text to a `WhitespaceToken`.
     pub fn from_text(text: &str, pos: Position) -> Result<Self> {
-        let value = if let Some(c) = text.chars().next() {
-            match c {
-                ' ' => Whitespace::Space,
-                '\t' => Whitespace::Tab,
-                '\r' => Whitespace::Return,
-                '\n' => Whitespace::Newline,
-                '\u{a0}' => Whitespace::NoBreakSpace,
-                _ => return Err(Error::invalid_whitespace_token(pos)),
-            }
-        } else {
+        let Some(value) = text.chars().next().and_then(Whitespace::from_char) else {
             return Err(Error::invalid_whitespace_token(pos));
         };
         Ok(WhitespaceToken { value, pos })
@@ -1524,6 +2325,40 @@ impl WhitespaceToken {
     pub fn text(&self) -> &'static str {
         self.value.as_str()
     }
+
+    /// Returns the length of the text of this token in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.text().len()
+    }
+
+    /// Returns the number of characters in the text of this token.
+    pub fn char_len(&self) -> usize {
+        self.text().chars().count()
+    }
+
+    /// Returns the number of newlines contained in this token.
+    ///
+    /// Since a whitespace token consists of a single character, the result is `0` or `1`.
+    /// Use [`whitespace_newline_count`](crate::whitespace_newline_count) to count the newlines
+    /// of a run of whitespace tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::Position;
+    /// use erl_tokenize::tokens::WhitespaceToken;
+    ///
+    /// let pos = Position::new();
+    /// assert_eq!(WhitespaceToken::from_text("\n", pos.clone()).unwrap().newline_count(), 1);
+    /// assert_eq!(WhitespaceToken::from_text(" ", pos.clone()).unwrap().newline_count(), 0);
+    /// ```
+    pub fn newline_count(&self) -> usize {
+        usize::from(self.value == Whitespace::Newline)
+    }
+
+    pub(crate) fn set_position(&mut self, pos: Position) {
+        self.pos = pos;
+    }
 }
 impl PositionRange for WhitespaceToken {
     fn start_position(&self) -> Position {
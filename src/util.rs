@@ -1,8 +1,11 @@
+use crate::values::Keyword;
 use crate::{Error, Position, Result};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use core::char;
+use core::iter::Peekable;
 use num::Num;
-use std::borrow::Cow;
-use std::char;
-use std::iter::Peekable;
 
 pub fn is_atom_head_char(c: char) -> bool {
     if let 'a'..='z' = c {
@@ -20,11 +23,11 @@ pub fn is_atom_non_head_char(c: char) -> bool {
 }
 
 pub fn is_variable_head_char(c: char) -> bool {
-    matches!(c, 'A'..='Z' | '_')
+    matches!(c, 'A'..='Z' | '_') || c.is_uppercase()
 }
 
 pub fn is_variable_non_head_char(c: char) -> bool {
-    matches!(c, 'a'..='z' | 'A'..='Z' | '@' | '_' | '0'..='9')
+    is_atom_non_head_char(c)
 }
 
 pub fn parse_quotation(
@@ -61,6 +64,22 @@ fn parse_quotation_owned(pos: Position, input: &str, terminator: char) -> Result
     Err(Error::no_closing_quotation(pos))
 }
 
+pub fn unescape(pos: Position, s: &str) -> Result<Cow<'_, str>> {
+    if !s.contains('\\') {
+        return Ok(Cow::Borrowed(s));
+    }
+    let mut buf = String::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            buf.push(parse_escaped_char(pos.clone() + i, &mut chars)?);
+        } else {
+            buf.push(c);
+        }
+    }
+    Ok(Cow::Owned(buf))
+}
+
 // http://erlang.org/doc/reference_manual/data_types.html#id76758
 pub fn parse_escaped_char<I>(pos: Position, chars: &mut Peekable<I>) -> Result<char>
 where
@@ -80,7 +99,11 @@ where
         'v' => Ok(11 as char), // Vertical Tabulation
         '^' => {
             let (_, c) = chars.next().ok_or_else(error)?;
-            Ok((c as u32 % 32) as u8 as char)
+            match c {
+                '?' => Ok(0x7F as char), // Delete
+                '@'..='_' | 'a'..='z' => Ok((c as u32 & 0x1F) as u8 as char),
+                _ => Err(error()),
+            }
         }
         'x' => {
             let (_, c) = chars.next().ok_or_else(error)?;
@@ -111,3 +134,48 @@ where
         _ => Ok(c),
     }
 }
+
+pub fn push_escaped_char(buf: &mut String, c: char, quote: Option<char>) {
+    match c {
+        '\\' => buf.push_str(r"\\"),
+        '\u{8}' => buf.push_str(r"\b"),
+        '\u{7F}' => buf.push_str(r"\d"),
+        '\u{1B}' => buf.push_str(r"\e"),
+        '\u{C}' => buf.push_str(r"\f"),
+        '\n' => buf.push_str(r"\n"),
+        '\r' => buf.push_str(r"\r"),
+        '\t' => buf.push_str(r"\t"),
+        '\u{B}' => buf.push_str(r"\v"),
+        _ if Some(c) == quote => {
+            buf.push('\\');
+            buf.push(c);
+        }
+        _ if c.is_control() => buf.push_str(&format!(r"\x{:02X}", c as u32)),
+        _ => buf.push(c),
+    }
+}
+
+pub fn quote(value: &str, quote: char) -> String {
+    let mut buf = String::with_capacity(value.len() + 2);
+    buf.push(quote);
+    for c in value.chars() {
+        push_escaped_char(&mut buf, c, Some(quote));
+    }
+    buf.push(quote);
+    buf
+}
+
+pub fn is_unquoted_atom(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(is_atom_head_char)
+        && chars.all(is_atom_non_head_char)
+        && Keyword::from_name(value).is_none()
+}
+
+pub fn format_float(value: f64) -> String {
+    let s = format!("{:?}", value);
+    match s.find('e') {
+        Some(i) if !s[..i].contains('.') => format!("{}.0{}", &s[..i], &s[i..]),
+        _ => s,
+    }
+}
@@ -1,4 +1,5 @@
 //! Token values.
+use core::fmt;
 
 /// Keyword (a.k.a., reserved word).
 ///
@@ -6,6 +7,7 @@
 ///
 /// [Reserved Words]: http://erlang.org/doc/reference_manual/introduction.html#id61721
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Keyword {
     /// `after`
     After,
@@ -129,10 +131,99 @@ impl Keyword {
             Keyword::Else => "else",
         }
     }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        let keyword = match name {
+            "after" => Keyword::After,
+            "and" => Keyword::And,
+            "andalso" => Keyword::Andalso,
+            "band" => Keyword::Band,
+            "begin" => Keyword::Begin,
+            "bnot" => Keyword::Bnot,
+            "bor" => Keyword::Bor,
+            "bsl" => Keyword::Bsl,
+            "bsr" => Keyword::Bsr,
+            "bxor" => Keyword::Bxor,
+            "case" => Keyword::Case,
+            "catch" => Keyword::Catch,
+            "cond" => Keyword::Cond,
+            "div" => Keyword::Div,
+            "end" => Keyword::End,
+            "fun" => Keyword::Fun,
+            "if" => Keyword::If,
+            "let" => Keyword::Let,
+            "not" => Keyword::Not,
+            "of" => Keyword::Of,
+            "or" => Keyword::Or,
+            "orelse" => Keyword::Orelse,
+            "receive" => Keyword::Receive,
+            "rem" => Keyword::Rem,
+            "try" => Keyword::Try,
+            "when" => Keyword::When,
+            "xor" => Keyword::Xor,
+            "maybe" => Keyword::Maybe,
+            "else" => Keyword::Else,
+            _ => return None,
+        };
+        Some(keyword)
+    }
+
+    /// Returns the arities with which this keyword can be used as an operator.
+    ///
+    /// The result is empty if this keyword is not an operator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::values::Keyword;
+    ///
+    /// assert_eq!(Keyword::Bnot.arities(), [1]);
+    /// assert_eq!(Keyword::Div.arities(), [2]);
+    /// assert!(Keyword::Case.arities().is_empty());
+    /// ```
+    pub fn arities(self) -> &'static [u8] {
+        match self {
+            Keyword::Bnot | Keyword::Not | Keyword::Catch => &[1],
+            Keyword::And
+            | Keyword::Andalso
+            | Keyword::Band
+            | Keyword::Bor
+            | Keyword::Bsl
+            | Keyword::Bsr
+            | Keyword::Bxor
+            | Keyword::Div
+            | Keyword::Or
+            | Keyword::Orelse
+            | Keyword::Rem
+            | Keyword::Xor => &[2],
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+impl AsRef<str> for Keyword {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
 }
 
 /// Symbol.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::values::Symbol;
+///
+/// assert_eq!(Symbol::DoubleColon.to_string(), "::");
+/// assert_eq!(format!("unexpected `{}`", Symbol::DoubleColon), "unexpected `::`");
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub enum Symbol {
     /// `[`
     OpenSquare,
@@ -230,6 +321,12 @@ pub enum Symbol {
     /// `<=`
     DoubleLeftArrow,
 
+    /// `<:-`
+    StrictLeftArrow,
+
+    /// `<:=`
+    StrictDoubleLeftArrow,
+
     /// `>>`
     DoubleRightAngle,
 
@@ -261,6 +358,67 @@ pub enum Symbol {
     LessEq,
 }
 impl Symbol {
+    const ALL: [Symbol; 44] = [
+        Symbol::OpenSquare,
+        Symbol::CloseSquare,
+        Symbol::OpenParen,
+        Symbol::CloseParen,
+        Symbol::OpenBrace,
+        Symbol::CloseBrace,
+        Symbol::Sharp,
+        Symbol::Slash,
+        Symbol::Dot,
+        Symbol::DoubleDot,
+        Symbol::TripleDot,
+        Symbol::Comma,
+        Symbol::Colon,
+        Symbol::DoubleColon,
+        Symbol::Semicolon,
+        Symbol::Match,
+        Symbol::MapMatch,
+        Symbol::VerticalBar,
+        Symbol::DoubleVerticalBar,
+        Symbol::Question,
+        Symbol::DoubleQuestion,
+        Symbol::MaybeMatch,
+        Symbol::Not,
+        Symbol::Hyphen,
+        Symbol::MinusMinus,
+        Symbol::Plus,
+        Symbol::PlusPlus,
+        Symbol::Multiply,
+        Symbol::RightArrow,
+        Symbol::LeftArrow,
+        Symbol::DoubleRightArrow,
+        Symbol::DoubleLeftArrow,
+        Symbol::StrictLeftArrow,
+        Symbol::StrictDoubleLeftArrow,
+        Symbol::DoubleRightAngle,
+        Symbol::DoubleLeftAngle,
+        Symbol::Eq,
+        Symbol::ExactEq,
+        Symbol::NotEq,
+        Symbol::ExactNotEq,
+        Symbol::Greater,
+        Symbol::GreaterEq,
+        Symbol::Less,
+        Symbol::LessEq,
+    ];
+
+    /// Returns all the symbols in the order of their declarations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::values::Symbol;
+    ///
+    /// assert_eq!(Symbol::all()[0], Symbol::OpenSquare);
+    /// assert!(Symbol::all().contains(&Symbol::ExactEq));
+    /// ```
+    pub const fn all() -> &'static [Symbol] {
+        &Self::ALL
+    }
+
     /// Returns the textual representation of this symbol.
     pub fn as_str(self) -> &'static str {
         match self {
@@ -295,6 +453,8 @@ impl Symbol {
             Symbol::LeftArrow => "<-",
             Symbol::DoubleRightArrow => "=>",
             Symbol::DoubleLeftArrow => "<=",
+            Symbol::StrictLeftArrow => "<:-",
+            Symbol::StrictDoubleLeftArrow => "<:=",
             Symbol::DoubleRightAngle => ">>",
             Symbol::DoubleLeftAngle => "<<",
             Symbol::Eq => "==",
@@ -308,10 +468,168 @@ impl Symbol {
             Symbol::MaybeMatch => "?=",
         }
     }
+
+    /// Returns the category of this symbol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::values::{Symbol, SymbolCategory};
+    ///
+    /// assert_eq!(Symbol::OpenParen.category(), SymbolCategory::Bracket);
+    /// assert_eq!(Symbol::Comma.category(), SymbolCategory::Separator);
+    /// assert_eq!(Symbol::PlusPlus.category(), SymbolCategory::Operator);
+    /// assert_eq!(Symbol::DoubleLeftAngle.category(), SymbolCategory::BitSyntax);
+    /// ```
+    pub fn category(self) -> SymbolCategory {
+        match self {
+            Symbol::OpenSquare
+            | Symbol::CloseSquare
+            | Symbol::OpenParen
+            | Symbol::CloseParen
+            | Symbol::OpenBrace
+            | Symbol::CloseBrace => SymbolCategory::Bracket,
+            Symbol::DoubleLeftAngle | Symbol::DoubleRightAngle => SymbolCategory::BitSyntax,
+            Symbol::Sharp
+            | Symbol::Dot
+            | Symbol::DoubleDot
+            | Symbol::TripleDot
+            | Symbol::Comma
+            | Symbol::Colon
+            | Symbol::DoubleColon
+            | Symbol::Semicolon
+            | Symbol::VerticalBar
+            | Symbol::DoubleVerticalBar
+            | Symbol::Question
+            | Symbol::DoubleQuestion
+            | Symbol::RightArrow
+            | Symbol::LeftArrow
+            | Symbol::DoubleLeftArrow
+            | Symbol::StrictLeftArrow
+            | Symbol::StrictDoubleLeftArrow
+            | Symbol::DoubleRightArrow
+            | Symbol::MapMatch => SymbolCategory::Separator,
+            Symbol::Slash
+            | Symbol::Match
+            | Symbol::MaybeMatch
+            | Symbol::Not
+            | Symbol::Hyphen
+            | Symbol::MinusMinus
+            | Symbol::Plus
+            | Symbol::PlusPlus
+            | Symbol::Multiply
+            | Symbol::Eq
+            | Symbol::ExactEq
+            | Symbol::NotEq
+            | Symbol::ExactNotEq
+            | Symbol::Greater
+            | Symbol::GreaterEq
+            | Symbol::Less
+            | Symbol::LessEq => SymbolCategory::Operator,
+        }
+    }
+
+    /// Returns `true` if this symbol is an operator, otherwise `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::values::Symbol;
+    ///
+    /// assert!(Symbol::Plus.is_operator());
+    /// assert!(!Symbol::Semicolon.is_operator());
+    /// ```
+    pub fn is_operator(self) -> bool {
+        self.category() == SymbolCategory::Operator
+    }
+
+    /// Returns the arities with which this symbol can be used as an operator.
+    ///
+    /// `-` and `+` can be both unary and binary operators.
+    /// The result is empty if this symbol is not an operator (see [`Symbol::is_operator`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::values::Symbol;
+    ///
+    /// assert_eq!(Symbol::Hyphen.arities(), [1, 2]);
+    /// assert_eq!(Symbol::Multiply.arities(), [2]);
+    /// assert!(Symbol::OpenParen.arities().is_empty());
+    /// assert!(Symbol::LeftArrow.arities().is_empty());
+    /// ```
+    pub fn arities(self) -> &'static [u8] {
+        match self {
+            Symbol::Hyphen | Symbol::Plus => &[1, 2],
+            _ if self.is_operator() => &[2],
+            _ => &[],
+        }
+    }
+
+    /// Returns the bracket which matches this symbol.
+    ///
+    /// For an opening bracket this returns the closing one and vice versa.
+    /// `<<` and `>>` are also treated as a pair of brackets.
+    /// The result is `None` if this symbol is not a bracket.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::values::Symbol;
+    ///
+    /// assert_eq!(Symbol::OpenParen.matching(), Some(Symbol::CloseParen));
+    /// assert_eq!(Symbol::CloseSquare.matching(), Some(Symbol::OpenSquare));
+    /// assert_eq!(Symbol::DoubleLeftAngle.matching(), Some(Symbol::DoubleRightAngle));
+    /// assert_eq!(Symbol::Comma.matching(), None);
+    /// ```
+    pub fn matching(self) -> Option<Self> {
+        match self {
+            Symbol::OpenSquare => Some(Symbol::CloseSquare),
+            Symbol::CloseSquare => Some(Symbol::OpenSquare),
+            Symbol::OpenParen => Some(Symbol::CloseParen),
+            Symbol::CloseParen => Some(Symbol::OpenParen),
+            Symbol::OpenBrace => Some(Symbol::CloseBrace),
+            Symbol::CloseBrace => Some(Symbol::OpenBrace),
+            Symbol::DoubleLeftAngle => Some(Symbol::DoubleRightAngle),
+            Symbol::DoubleRightAngle => Some(Symbol::DoubleLeftAngle),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Category of symbols.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SymbolCategory {
+    /// Brackets (e.g., `(`, `]`, `{`).
+    Bracket,
+
+    /// Separators and other punctuation (e.g., `,`, `;`, `:`, `->`, `<-`, `=>`).
+    Separator,
+
+    /// Operators (e.g., `+`, `==`, `!`, `=`).
+    Operator,
+
+    /// Bit syntax delimiters (i.e., `<<` and `>>`).
+    BitSyntax,
 }
 
 /// White space.
+///
+/// Note that new variants may be added in the future.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[non_exhaustive]
 pub enum Whitespace {
     /// `' '`
     Space,
@@ -325,10 +643,39 @@ pub enum Whitespace {
     /// `'\n'`
     Newline,
 
+    /// `'\u{B}'`
+    VerticalTab,
+
+    /// `'\u{C}'`
+    FormFeed,
+
     /// `'\u{A0}'`
     NoBreakSpace,
 }
 impl Whitespace {
+    /// Returns the whitespace corresponding to the character `c`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use erl_tokenize::values::Whitespace;
+    ///
+    /// assert_eq!(Whitespace::from_char('\t'), Some(Whitespace::Tab));
+    /// assert_eq!(Whitespace::from_char('a'), None);
+    /// ```
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            ' ' => Some(Whitespace::Space),
+            '\t' => Some(Whitespace::Tab),
+            '\r' => Some(Whitespace::Return),
+            '\n' => Some(Whitespace::Newline),
+            '\u{B}' => Some(Whitespace::VerticalTab),
+            '\u{C}' => Some(Whitespace::FormFeed),
+            '\u{A0}' => Some(Whitespace::NoBreakSpace),
+            _ => None,
+        }
+    }
+
     /// Coverts to the corresponding character.
     pub fn as_char(self) -> char {
         match self {
@@ -336,6 +683,8 @@ impl Whitespace {
             Whitespace::Tab => '\t',
             Whitespace::Return => '\r',
             Whitespace::Newline => '\n',
+            Whitespace::VerticalTab => '\u{B}',
+            Whitespace::FormFeed => '\u{C}',
             Whitespace::NoBreakSpace => '\u{A0}',
         }
     }
@@ -347,7 +696,40 @@ impl Whitespace {
             Whitespace::Tab => "\t",
             Whitespace::Return => "\r",
             Whitespace::Newline => "\n",
+            Whitespace::VerticalTab => "\u{B}",
+            Whitespace::FormFeed => "\u{C}",
             Whitespace::NoBreakSpace => "\u{A0}",
         }
     }
 }
+
+/// Kind of sigils (see [EEP 66](https://www.erlang.org/eeps/eep-0066)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum SigilKind<'a> {
+    /// `~` (the vanilla sigil which makes a UTF-8 encoded binary).
+    Default,
+
+    /// `~b` (a UTF-8 encoded binary with escape sequences).
+    Binary,
+
+    /// `~s` (a string with escape sequences).
+    String,
+
+    /// `~B` and `~S` (a binary or a string without escape sequences).
+    Verbatim,
+
+    /// Other (user-defined) sigils.
+    Custom(&'a str),
+}
+impl<'a> SigilKind<'a> {
+    /// Returns the kind corresponding to the given sigil prefix (e.g., `"b"` for `~b`).
+    pub fn from_prefix(prefix: &'a str) -> Self {
+        match prefix {
+            "" => SigilKind::Default,
+            "b" => SigilKind::Binary,
+            "s" => SigilKind::String,
+            "B" | "S" => SigilKind::Verbatim,
+            _ => SigilKind::Custom(prefix),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::tokens::{
+    AtomToken, CharToken, CommentToken, EofToken, FloatToken, IntegerToken, KeywordToken,
+    SigilStringToken, StringToken, SymbolToken, VariableToken, WhitespaceToken,
+};
+use crate::{Result, Token, Tokenizer};
+
+/// Visitor which is called back for each token by [`visit_tokens`].
+///
+/// All the methods do nothing by default, so implement only the ones for the token types of interest.
+///
+/// # Examples
+///
+/// ```
+/// use erl_tokenize::{visit_tokens, TokenVisitor};
+/// use erl_tokenize::tokens::VariableToken;
+///
+/// #[derive(Default)]
+/// struct Variables(Vec<String>);
+/// impl TokenVisitor for Variables {
+///     fn on_variable(&mut self, token: &VariableToken) {
+///         self.0.push(token.value().to_owned());
+///     }
+/// }
+///
+/// let mut visitor = Variables::default();
+/// visit_tokens("foo(X, Y) -> X + Y.", &mut visitor).unwrap();
+/// assert_eq!(visitor.0, ["X", "Y", "X", "Y"]);
+/// ```
+#[allow(unused_variables)]
+pub trait TokenVisitor {
+    /// Called for each atom token.
+    fn on_atom(&mut self, token: &AtomToken) {}
+
+    /// Called for each character token.
+    fn on_char(&mut self, token: &CharToken) {}
+
+    /// Called for each comment token.
+    fn on_comment(&mut self, token: &CommentToken) {}
+
+    /// Called for an end-of-file token.
+    fn on_eof(&mut self, token: &EofToken) {}
+
+    /// Called for each float token.
+    fn on_float(&mut self, token: &FloatToken) {}
+
+    /// Called for each integer token.
+    fn on_integer(&mut self, token: &IntegerToken) {}
+
+    /// Called for each keyword token.
+    fn on_keyword(&mut self, token: &KeywordToken) {}
+
+    /// Called for each sigil string token.
+    fn on_sigil_string(&mut self, token: &SigilStringToken) {}
+
+    /// Called for each string token.
+    fn on_string(&mut self, token: &StringToken) {}
+
+    /// Called for each symbol token.
+    fn on_symbol(&mut self, token: &SymbolToken) {}
+
+    /// Called for each variable token.
+    fn on_variable(&mut self, token: &VariableToken) {}
+
+    /// Called for each whitespace token.
+    fn on_whitespace(&mut self, token: &WhitespaceToken) {}
+
+    /// Called for each token.
+    ///
+    /// The default implementation dispatches the token to the method corresponding to its type.
+    fn on_token(&mut self, token: &Token) {
+        match token {
+            Token::Atom(t) => self.on_atom(t),
+            Token::Char(t) => self.on_char(t),
+            Token::Comment(t) => self.on_comment(t),
+            Token::Eof(t) => self.on_eof(t),
+            Token::Float(t) => self.on_float(t),
+            Token::Integer(t) => self.on_integer(t),
+            Token::Keyword(t) => self.on_keyword(t),
+            Token::SigilString(t) => self.on_sigil_string(t),
+            Token::String(t) => self.on_string(t),
+            Token::Symbol(t) => self.on_symbol(t),
+            Token::Variable(t) => self.on_variable(t),
+            Token::Whitespace(t) => self.on_whitespace(t),
+        }
+    }
+}
+
+/// Tokenizes the text and calls back the visitor for each token.
+///
+/// If a tokenization error occurs, this function returns it immediately
+/// (the visitor has been called for the tokens before the error).
+pub fn visit_tokens<V: TokenVisitor + ?Sized>(text: &str, visitor: &mut V) -> Result<()> {
+    for token in Tokenizer::new(text) {
+        visitor.on_token(&token?);
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use erl_tokenize::{Token, Tokenizer};
+use erl_tokenize::{Position, PositionRange, Token, TokenKind, Tokenizer};
 
 macro_rules! tokenize {
     ($text:expr) => {
@@ -53,6 +53,15 @@ fn tokenize_atoms() {
 fn tokenize_variables() {
     let src = "Foo BAR _ _Baz";
     assert_eq!(tokenize!(src), ["Foo", " ", "BAR", " ", "_", " ", "_Baz"]);
+
+    let src = "_1 __ _@node X1@a_B";
+    assert_eq!(
+        tokenize!(src),
+        ["_1", " ", "__", " ", "_@node", " ", "X1@a_B"]
+    );
+    for t in Tokenizer::new(src).filter_map(|t| t.unwrap().into_lexical_token().ok()) {
+        assert!(t.as_variable_token().is_some(), "{t:?}");
+    }
 }
 
 #[test]
@@ -193,3 +202,2764 @@ fn tokenize_multibyte_whitespaces() {
     let src = "a\u{a0}b";
     assert_eq!(tokenize!(src), ["a", "\u{a0}", "b"]);
 }
+
+#[test]
+fn to_erl_scan_terms() {
+    let src = r#"io:format("x")."#;
+    let terms = Tokenizer::new(src)
+        .map(|t| t.unwrap().to_erl_scan_term())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        terms,
+        [
+            "{atom,1,io}",
+            "{':',1}",
+            "{atom,1,format}",
+            "{'(',1}",
+            r#"{string,1,"x"}"#,
+            "{')',1}",
+            "{dot,1}"
+        ]
+    );
+
+    let src = "'Foo' 'case' 10 1.5e20 $a X case";
+    let terms = Tokenizer::new(src)
+        .map(|t| t.unwrap())
+        .filter(|t| t.is_lexical_token())
+        .map(|t| t.to_erl_scan_term())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        terms,
+        [
+            "{atom,1,'Foo'}",
+            "{atom,1,'case'}",
+            "{integer,1,10}",
+            "{float,1,1.5e20}",
+            "{char,1,97}",
+            "{var,1,'X'}",
+            "{'case',1}"
+        ]
+    );
+}
+
+#[test]
+fn terminator_dots() {
+    fn terminator_dots(text: &str) -> Vec<bool> {
+        let tokens = Tokenizer::new(text).collect::<Result<Vec<_>, _>>().unwrap();
+        tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.text() == ".")
+            .map(|(i, t)| t.is_terminator_dot(tokens.get(i + 1)))
+            .collect()
+    }
+
+    assert_eq!(terminator_dots("foo."), [true]);
+    assert_eq!(terminator_dots("foo.\nbar. % baz"), [true, true]);
+    assert_eq!(terminator_dots("foo.% bar"), [true]);
+    assert_eq!(terminator_dots("R#r.field"), [false]);
+    assert_eq!(terminator_dots("a.b"), [false]);
+}
+
+#[test]
+fn fold_signs() {
+    fn fold_signs(text: &str) -> Vec<String> {
+        Tokenizer::new(text)
+            .fold_signs()
+            .map(|t| t.unwrap().text().to_string())
+            .filter(|t| t != " ")
+            .collect()
+    }
+
+    assert_eq!(fold_signs("[-1, +2]"), ["[", "-1", ",", "+2", "]"]);
+    assert_eq!(fold_signs("3 - 4"), ["3", "-", "4"]);
+    assert_eq!(fold_signs("X -4"), ["X", "-", "4"]);
+    assert_eq!(fold_signs("f() -4"), ["f", "(", ")", "-", "4"]);
+    assert_eq!(fold_signs("-1.5 - -2"), ["-1.5", "-", "-2"]);
+    assert_eq!(fold_signs("- 1"), ["-", "1"]);
+    assert_eq!(fold_signs("case -1 of"), ["case", "-1", "of"]);
+
+    let tokens = Tokenizer::new("-1.5 [-10]")
+        .fold_signs()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens[0].as_float_token().map(|t| t.value()), Some(1.5));
+    assert_eq!(
+        tokens[0].as_float_token().map(|t| t.is_negative()),
+        Some(true)
+    );
+    assert_eq!(tokens[0].end_position().offset(), 4);
+    assert_eq!(tokens[3].text(), "-10");
+    assert_eq!(
+        tokens[3].as_integer_token().map(|t| t.value().clone()),
+        Some(num::BigUint::from(10u32))
+    );
+    assert_eq!(
+        tokens[3].as_integer_token().map(|t| t.is_negative()),
+        Some(true)
+    );
+
+    // The iteration stops after the error.
+    let mut iter = Tokenizer::new("[-\"foo").fold_signs();
+    assert_eq!(iter.next().unwrap().unwrap().text(), "[");
+    assert_eq!(iter.next().unwrap().unwrap().text(), "-");
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn content_ranges() {
+    fn content(text: &str) -> &str {
+        let token = Tokenizer::new(text).next().unwrap().unwrap();
+        let range = match token {
+            Token::Atom(t) => t.content_range(),
+            Token::Char(t) => t.content_range(),
+            Token::SigilString(t) => t.content_range(),
+            Token::String(t) => t.content_range(),
+            t => panic!("{t:?}"),
+        };
+        &text[range]
+    }
+
+    assert_eq!(content(r#""foo""#), "foo");
+    assert_eq!(content(r#""""#), "");
+    assert_eq!(content(r#""f\"o""#), r#"f\"o"#);
+    assert_eq!(content("\"\"\"\n  foo\n  bar\n  \"\"\""), "  foo\n  bar");
+    assert_eq!(content("\"\"\"\"\n\"\"\"\n\"\"\"\""), "\"\"\"");
+    assert_eq!(content("\"\"\"\n\"\"\""), "");
+    assert_eq!(content("'foo'"), "foo");
+    assert_eq!(content("foo"), "foo");
+    assert_eq!(content(r"$\n"), r"\n");
+    assert_eq!(content("~b[foo]"), "foo");
+    assert_eq!(content("~\"foo\"x"), "foo");
+    assert_eq!(content("~S\"\"\"\n  foo\n  \"\"\""), "  foo");
+}
+
+#[test]
+fn tokenize_bit_syntax() {
+    let src = "<<X:8/integer-unsigned-big>>";
+    assert_eq!(
+        tokenize!(src),
+        ["<<", "X", ":", "8", "/", "integer", "-", "unsigned", "-", "big", ">>"]
+    );
+
+    assert_eq!(tokenize!("<<A>>"), ["<<", "A", ">>"]);
+    assert_eq!(tokenize!("<<<<A>>>>"), ["<<", "<<", "A", ">>", ">>"]);
+    assert_eq!(tokenize!("X <= Y"), ["X", " ", "<=", " ", "Y"]);
+    assert_eq!(
+        tokenize!("<<X>> <= B"),
+        ["<<", "X", ">>", " ", "<=", " ", "B"]
+    );
+    assert_eq!(tokenize!("X <- L"), ["X", " ", "<-", " ", "L"]);
+    assert_eq!(tokenize!("X =< Y"), ["X", " ", "=<", " ", "Y"]);
+}
+
+#[test]
+fn tokenize_strict_generators() {
+    use erl_tokenize::tokens::SymbolToken;
+    use erl_tokenize::values::Symbol;
+    use erl_tokenize::Position;
+
+    assert_eq!(
+        tokenize!("[X || X <:- L]"),
+        ["[", "X", " ", "||", " ", "X", " ", "<:-", " ", "L", "]"]
+    );
+    assert_eq!(
+        tokenize!("<< <<X>> || <<X>> <:= B >>"),
+        [
+            "<<", " ", "<<", "X", ">>", " ", "||", " ", "<<", "X", ">>", " ", "<:=", " ", "B", " ",
+            ">>"
+        ]
+    );
+
+    for symbol in [Symbol::StrictLeftArrow, Symbol::StrictDoubleLeftArrow] {
+        let text = SymbolToken::from_value(symbol, Position::new()).text();
+        let token = SymbolToken::from_text(text, Position::new()).unwrap();
+        assert_eq!(token.value(), symbol);
+        assert_eq!(token.end_position().offset(), 3);
+    }
+    assert_eq!(Symbol::StrictLeftArrow.as_str(), "<:-");
+    assert_eq!(Symbol::StrictDoubleLeftArrow.as_str(), "<:=");
+}
+
+#[test]
+fn symbol_categories() {
+    use erl_tokenize::values::{Symbol, SymbolCategory};
+
+    for s in [Symbol::OpenSquare, Symbol::CloseBrace, Symbol::OpenParen] {
+        assert_eq!(s.category(), SymbolCategory::Bracket);
+    }
+    for s in [
+        Symbol::Comma,
+        Symbol::Semicolon,
+        Symbol::Colon,
+        Symbol::RightArrow,
+        // Comprehension generators and map associations.
+        Symbol::LeftArrow,
+        Symbol::DoubleLeftArrow,
+        Symbol::StrictLeftArrow,
+        Symbol::StrictDoubleLeftArrow,
+        Symbol::DoubleRightArrow,
+        Symbol::MapMatch,
+    ] {
+        assert_eq!(s.category(), SymbolCategory::Separator);
+        assert!(!s.is_operator());
+    }
+    for s in [
+        Symbol::Plus,
+        Symbol::Hyphen,
+        Symbol::Multiply,
+        Symbol::Slash,
+        Symbol::Eq,
+        Symbol::ExactEq,
+        Symbol::PlusPlus,
+        Symbol::MinusMinus,
+        Symbol::Not,
+        Symbol::Match,
+    ] {
+        assert_eq!(s.category(), SymbolCategory::Operator);
+        assert!(s.is_operator());
+    }
+    for s in [Symbol::DoubleLeftAngle, Symbol::DoubleRightAngle] {
+        assert_eq!(s.category(), SymbolCategory::BitSyntax);
+    }
+}
+
+#[test]
+fn retokenize() {
+    use erl_tokenize::Edit;
+
+    fn summary(tokens: &[Token]) -> Vec<(String, usize, usize, usize)> {
+        tokens
+            .iter()
+            .map(|t| {
+                let p = t.start_position();
+                (t.text().to_owned(), p.offset(), p.line(), p.column())
+            })
+            .collect()
+    }
+
+    let old_text = "-module(foo).\n\nfoo() -> 1.\n\nbar(X) ->\n    X + 2.\n\nbaz() -> \"a\nb\".\n";
+    let old_tokens = Tokenizer::new(old_text)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let at = |s: &str| old_text.find(s).unwrap();
+    let edits = [
+        Edit::new(at("1.")..at("1.") + 1, "10"),
+        Edit::new(at("1.")..at("1.") + 1, "1.5"),
+        Edit::new(at("1.") + 1..at("1.") + 1, "5"),
+        Edit::new(0..0, "%% header\n"),
+        Edit::new(at("foo()")..at("foo()"), "qux() -> ok.\n\n"),
+        Edit::new(at("bar")..at("bar"), "\n\n"),
+        Edit::new(at("X + 2")..at("X + 2") + 5, ""),
+        Edit::new(at("2.")..at("2.") + 1, "\"x\ny\""),
+        Edit::new(at("\"a")..at("\"a") + 5, "ok"),
+        Edit::new(old_text.len()..old_text.len(), "end."),
+    ];
+    for edit in &edits {
+        let mut new_text = old_text.to_owned();
+        new_text.replace_range(edit.range(), edit.text());
+
+        let expected = Tokenizer::new(&new_text)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut tokenizer = Tokenizer::new(&new_text);
+        let actual = tokenizer.retokenize(edit, &old_tokens).unwrap();
+        assert_eq!(summary(&actual), summary(&expected), "{edit:?}");
+        assert_eq!(tokenizer.next_position().offset(), new_text.len());
+        assert!(tokenizer.next().is_none());
+    }
+}
+
+#[test]
+fn caret_escapes() {
+    fn value(text: &str) -> Result<char, usize> {
+        match Tokenizer::new(text).next() {
+            Some(Ok(Token::Char(t))) => Ok(t.value()),
+            Some(Err(e)) => Err(e.position().offset()),
+            t => panic!("{t:?}"),
+        }
+    }
+
+    assert_eq!(value(r"$\^a"), Ok('\u{1}'));
+    assert_eq!(value(r"$\^A"), Ok('\u{1}'));
+    assert_eq!(value(r"$\^z"), Ok('\u{1A}'));
+    assert_eq!(value(r"$\^]"), Ok('\u{1D}'));
+    assert_eq!(value(r"$\^_"), Ok('\u{1F}'));
+    assert_eq!(value(r"$\^@"), Ok('\u{0}'));
+    assert_eq!(value(r"$\^?"), Ok('\u{7F}'));
+    assert_eq!(value(r"$\^€"), Err(1));
+    assert_eq!(value(r"$\^ä"), Err(1));
+    assert_eq!(value(r"$\^1"), Err(1));
+    assert_eq!(value(r"$\^{"), Err(1));
+    assert_eq!(value(r"$\^"), Err(1));
+
+    let src = r#""a\^€""#;
+    assert!(Tokenizer::new(src).next().unwrap().is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn position_cmp_offset() {
+    use std::cmp::Ordering;
+
+    let positions = |path: &str| {
+        let mut tokenizer = Tokenizer::new("a b");
+        tokenizer.set_filepath(path);
+        tokenizer
+            .map(|t| t.unwrap().start_position())
+            .collect::<Vec<_>>()
+    };
+    let x = positions("x.erl");
+    let y = positions("y.erl");
+
+    assert_eq!(x[0].cmp_offset(&y[0]), Ordering::Equal);
+    assert_ne!(x[0], y[0]);
+    assert_eq!(x[2].cmp_offset(&y[0]), Ordering::Greater);
+    assert_eq!(x[2].cmp(&y[0]), Ordering::Less);
+
+    let mut all = x.iter().chain(y.iter()).cloned().collect::<Vec<_>>();
+    all.sort_by(|a, b| a.cmp_offset(b));
+    assert_eq!(
+        all.iter().map(|p| p.offset()).collect::<Vec<_>>(),
+        [0, 0, 1, 1, 2, 2]
+    );
+}
+
+#[test]
+fn spans() {
+    use erl_tokenize::Span;
+
+    let src = "foo(\"bar\nbaz\")";
+    let tokens = Tokenizer::new(src).collect::<Result<Vec<_>, _>>().unwrap();
+
+    let span = Span::from(&tokens[2]);
+    assert_eq!(span.start().offset(), 4);
+    assert_eq!(span.end().offset(), 13);
+    assert_eq!(span.end().line(), 2);
+    assert_eq!(span.len(), 9);
+    assert!(!span.is_empty());
+    assert!(!span.contains(3));
+    assert!(span.contains(4));
+    assert!(span.contains(12));
+    assert!(!span.contains(13));
+
+    let span = Span::of(&tokens[0]);
+    assert_eq!(span.start_position(), tokens[0].start_position());
+    assert_eq!(span.end_position(), tokens[0].end_position());
+
+    let empty = Span::new(span.end().clone(), span.end().clone());
+    assert!(empty.is_empty());
+    assert!(!empty.contains(3));
+}
+
+#[test]
+fn char_token_round_trip() {
+    use erl_tokenize::tokens::CharToken;
+    use erl_tokenize::Position;
+
+    let chars = (0..=32)
+        .chain([127, 0x80, 0x9F])
+        .filter_map(char::from_u32)
+        .chain(['a', '\\', '$', '\'', '"', 'ä', '€']);
+    for c in chars {
+        let text = CharToken::from_value(c, Position::new()).text().to_owned();
+        let token = CharToken::from_text(&text, Position::new()).unwrap();
+        assert_eq!(token.value(), c, "{text:?}");
+        assert_eq!(token.text(), text);
+        assert!(!text[1..].chars().any(|c| c.is_control()), "{text:?}");
+    }
+
+    let pos = Position::new;
+    assert_eq!(CharToken::from_value('\u{1B}', pos()).text(), r"$\e");
+    assert_eq!(CharToken::from_value('\u{7F}', pos()).text(), r"$\d");
+    assert_eq!(CharToken::from_value('\u{8}', pos()).text(), r"$\b");
+    assert_eq!(CharToken::from_value('\u{C}', pos()).text(), r"$\f");
+    assert_eq!(CharToken::from_value('\u{B}', pos()).text(), r"$\v");
+    assert_eq!(CharToken::from_value('\t', pos()).text(), r"$\t");
+    assert_eq!(CharToken::from_value('\\', pos()).text(), r"$\\");
+}
+
+#[test]
+fn string_token_round_trip() {
+    use erl_tokenize::tokens::StringToken;
+    use erl_tokenize::Position;
+
+    let values = [
+        "a\u{1b}b",
+        "foo",
+        "",
+        "\t\n\r\"\\",
+        "\u{0}\u{1}\u{7f}\u{9f}",
+        "\u{1}f",
+        "comté €",
+    ];
+    for value in values {
+        let text = StringToken::from_value(value, Position::new())
+            .text()
+            .to_owned();
+        assert!(!text.chars().any(|c| c.is_control()), "{text:?}");
+
+        let tokens = Tokenizer::new(&text)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].as_string_token().map(|t| t.value()), Some(value));
+    }
+}
+
+#[test]
+fn parse_integers() {
+    use erl_tokenize::parse::parse_integer;
+    use num::BigUint;
+
+    assert_eq!(parse_integer("16#ff").ok(), Some(BigUint::from(255u32)));
+    assert_eq!(parse_integer("2#1_0").ok(), Some(BigUint::from(2u32)));
+    assert_eq!(parse_integer("36#zz").ok(), Some(BigUint::from(1295u32)));
+    assert_eq!(parse_integer("123").ok(), Some(BigUint::from(123u32)));
+
+    assert_eq!(
+        parse_integer("1#0").map_err(|e| e.position().offset()),
+        Err(0)
+    );
+    assert_eq!(
+        parse_integer("123abc").map_err(|e| e.position().offset()),
+        Err(3)
+    );
+    assert_eq!(
+        parse_integer("16#ffg").map_err(|e| e.position().offset()),
+        Err(5)
+    );
+    assert!(parse_integer("").is_err());
+    assert!(parse_integer("-1").is_err());
+    assert!(parse_integer("1 ").is_err());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn render_diagnostics() {
+    let src = "-module(foo).\n\nfoo() ->\n\t\"a\"\"b\".\n";
+    let error = Tokenizer::new(src).find_map(|t| t.err()).unwrap();
+    assert_eq!(
+        error.render_diagnostic(src),
+        concat!(
+            "error: adjacent string literals without intervening white space\n",
+            " --> <unknown>:4:5\n",
+            "  |\n",
+            "4 | \t\"a\"\"b\".\n",
+            "  | \t   ^\n"
+        )
+    );
+
+    let src = "\n\n\n\n\n\n\n\n\n\nfoo $";
+    let mut tokenizer = Tokenizer::new(src);
+    tokenizer.set_filepath("foo.erl");
+    let error = tokenizer.find_map(|t| t.err()).unwrap();
+    assert_eq!(
+        error.render_diagnostic(src),
+        concat!(
+            "error: cannot parse a character token\n",
+            "  --> foo.erl:11:5\n",
+            "   |\n",
+            "11 | foo $\n",
+            "   |     ^\n"
+        )
+    );
+
+    // Snippets tokenized with a base position.
+    let src = "foo() ->\n  \"a\"\"b\".\n";
+    let error = Tokenizer::new(src)
+        .with_base_position(20, 9, 500)
+        .find_map(|t| t.err())
+        .unwrap();
+    assert_eq!(
+        error.render_diagnostic_with_base(src, 500),
+        concat!(
+            "error: adjacent string literals without intervening white space\n",
+            "  --> <unknown>:21:6\n",
+            "   |\n",
+            "21 |   \"a\"\"b\".\n",
+            "   |      ^\n"
+        )
+    );
+    assert_eq!(
+        error.render_diagnostic(src),
+        concat!(
+            "error: adjacent string literals without intervening white space\n",
+            "  --> <unknown>:21:6\n",
+            "   |\n",
+            "   = note: the position is outside of the source\n"
+        )
+    );
+}
+
+#[test]
+fn invalid_control_chars() {
+    use erl_tokenize::Error;
+
+    let mut tokenizer = Tokenizer::new("foo\u{0}bar");
+    assert_eq!(tokenizer.next().unwrap().unwrap().text(), "foo");
+    match tokenizer.next() {
+        Some(Err(Error::InvalidControlChar { position, ch })) => {
+            assert_eq!(ch, '\u{0}');
+            assert_eq!(position.offset(), 3);
+        }
+        t => panic!("{t:?}"),
+    }
+
+    for c in ['\u{1}', '\u{1F}', '\u{7F}', '\u{85}', '\u{9F}'] {
+        let text = c.to_string();
+        let error = Tokenizer::new(&text).next().unwrap().err();
+        assert!(
+            matches!(error, Some(Error::InvalidControlChar { ch, .. }) if ch == c),
+            "{error:?}"
+        );
+    }
+
+    // Vertical tabs and form feeds are white spaces as in Erlang.
+    assert_eq!(
+        tokenize!("foo\u{B}\u{C}bar"),
+        ["foo", "\u{B}", "\u{C}", "bar"]
+    );
+    let kinds = Tokenizer::new("\u{B}\u{C}")
+        .map(|t| t.unwrap().kind())
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, [TokenKind::Whitespace, TokenKind::Whitespace]);
+}
+
+#[test]
+fn by_line() {
+    let src = r#"-module(foo).
+-doc """
+  Hello
+  """.
+bar() -> ok."#;
+    let lines = Tokenizer::new(src)
+        .by_line()
+        .map(|item| {
+            let (line, tokens) = item.unwrap();
+            (
+                line,
+                tokens
+                    .iter()
+                    .map(|t| t.text().to_owned())
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        lines,
+        [
+            (1, vec!["-", "module", "(", "foo", ")", ".", "\n"]),
+            (2, vec!["-", "doc", " ", "\"\"\"\n  Hello\n  \"\"\""]),
+            (4, vec![".", "\n"]),
+            (5, vec!["bar", "(", ")", " ", "->", " ", "ok", "."]),
+        ]
+        .into_iter()
+        .map(|(line, texts)| (line, texts.into_iter().map(String::from).collect()))
+        .collect::<Vec<_>>()
+    );
+
+    // The error is yielded after the tokens preceding it, and then the iteration stops.
+    let mut iter = Tokenizer::new("foo.\nbar \"baz").by_line();
+    assert_eq!(iter.next().unwrap().unwrap().0, 1);
+    let (line, tokens) = iter.next().unwrap().unwrap();
+    assert_eq!(line, 2);
+    assert_eq!(
+        tokens.iter().map(|t| t.text()).collect::<Vec<_>>(),
+        ["bar", " "]
+    );
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn string_raw_lines() {
+    use erl_tokenize::tokens::StringToken;
+
+    let text = r#""""
+        foo
+          bar
+        baz
+        """"#;
+    let token = StringToken::from_text(text, Position::new()).unwrap();
+    assert_eq!(token.common_indent(), 8);
+    assert_eq!(
+        token.raw_lines().collect::<Vec<_>>(),
+        ["        foo", "          bar", "        baz"]
+    );
+    let dedented = token
+        .raw_lines()
+        .map(|line| line.get(token.common_indent()..).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert_eq!(dedented, "foo\n  bar\nbaz");
+
+    let token = StringToken::from_text("\"\"\"\n\"\"\"", Position::new()).unwrap();
+    assert_eq!(token.raw_lines().count(), 0);
+    let token = StringToken::from_text("\"\"\"\n\n\"\"\"", Position::new()).unwrap();
+    assert_eq!(token.raw_lines().collect::<Vec<_>>(), [""]);
+}
+
+#[test]
+fn custom_atom_head_char() {
+    let src = "$foo($bar, $a)";
+    let tokens = Tokenizer::new(src)
+        .with_atom_head_char(|c| c == '$' || c.is_lowercase())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        tokens
+            .iter()
+            .filter_map(|t| t.as_atom_token())
+            .map(|t| t.value())
+            .collect::<Vec<_>>(),
+        ["$foo", "$bar", "$a"]
+    );
+
+    // The default behavior is kept.
+    let tokens = Tokenizer::tokenize("$a").unwrap();
+    assert!(matches!(tokens[0], Token::Char(_)));
+}
+
+#[test]
+fn invalid_radixes() {
+    use erl_tokenize::Error;
+
+    for (text, expected) in [("1#0", 1), ("0#1", 0), ("37#z", 37)] {
+        match Tokenizer::new(text).next() {
+            Some(Err(Error::InvalidRadix { position, radix })) => {
+                assert_eq!(radix, expected);
+                assert_eq!(position.offset(), 0);
+            }
+            t => panic!("{text}: {t:?}"),
+        }
+    }
+
+    assert!(matches!(
+        Tokenizer::new("2#2").next(),
+        Some(Err(Error::InvalidIntegerToken { .. }))
+    ));
+    assert_eq!(Tokenizer::tokenize("36#z").unwrap()[0].text(), "36#z");
+}
+
+#[test]
+fn next_form() {
+    let src = "-module(foo).\nfoo() -> R#r.f. % comment\n";
+    let mut tokenizer = Tokenizer::new(src);
+
+    let form = tokenizer.next_form().unwrap().unwrap();
+    assert_eq!(
+        form.iter().map(|t| t.text()).collect::<Vec<_>>(),
+        ["-", "module", "(", "foo", ")", "."]
+    );
+
+    // The tokenizer is still usable.
+    assert_eq!(tokenizer.next().unwrap().unwrap().text(), "\n");
+    let form = tokenizer.next_form().unwrap().unwrap();
+    assert_eq!(
+        form.iter().map(|t| t.text()).collect::<Vec<_>>(),
+        ["foo", "(", ")", " ", "->", " ", "R", "#", "r", ".", "f", "."]
+    );
+
+    let form = tokenizer.next_form().unwrap().unwrap();
+    assert_eq!(
+        form.iter().map(|t| t.text()).collect::<Vec<_>>(),
+        [" ", "% comment", "\n"]
+    );
+    assert!(tokenizer.next_form().is_none());
+
+    let mut tokenizer = Tokenizer::new("foo(\"bar");
+    assert!(tokenizer.next_form().unwrap().is_err());
+    assert_eq!(tokenizer.next_position().offset(), 4);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_tokens() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use erl_tokenize::values::Symbol;
+
+    let mut seed = 1u32;
+    let data = (0..65536)
+        .map(|_| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            (seed >> 16) as u8
+        })
+        .collect::<Vec<_>>();
+    let mut u = Unstructured::new(&data);
+
+    let symbol = Symbol::arbitrary(&mut u).unwrap();
+    let token = Token::arbitrary_of_kind(TokenKind::Symbol, &mut u).unwrap();
+    assert_eq!(token.kind(), TokenKind::Symbol);
+    assert!(!symbol.as_str().is_empty());
+
+    while !u.is_empty() {
+        let token = Token::arbitrary(&mut u).unwrap();
+        if token.kind() == TokenKind::Eof {
+            assert_eq!(token.text(), "");
+            continue;
+        }
+        let retokenized = Token::from_text(token.text(), Position::new()).unwrap();
+        assert_eq!(retokenized.text(), token.text());
+    }
+}
+
+#[test]
+fn strict_mode() {
+    let src = "foo(\"bar, $";
+    let mut errors = 0;
+    for token in Tokenizer::new(src).strict_mode(true) {
+        if token.is_err() {
+            errors += 1;
+        }
+    }
+    assert_eq!(errors, 1);
+
+    let mut tokenizer = Tokenizer::new("foo. \"bar").strict_mode(true);
+    assert_eq!(tokenizer.next_form().unwrap().unwrap().len(), 2);
+    assert_eq!(tokenizer.next().unwrap().unwrap().text(), " ");
+    assert!(tokenizer.next().unwrap().is_err());
+    assert!(tokenizer.next().is_none());
+}
+
+#[test]
+fn highlight_classes() {
+    use erl_tokenize::HighlightClass::*;
+
+    let src = r#"foo(X) when X >= 1.0 -> [$a, "b" ++ ~s"c", 'd', 2]. % e"#;
+    let classes = Tokenizer::new(src)
+        .map(|t| t.unwrap().highlight_class())
+        .filter(|&c| c != Whitespace)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        classes,
+        [
+            Atom,
+            Punctuation,
+            Variable,
+            Punctuation,
+            Keyword,
+            Variable,
+            Operator,
+            Number,
+            Punctuation, // `->`
+            Punctuation,
+            Char,
+            Punctuation,
+            String,
+            Operator,
+            String,
+            Punctuation,
+            Atom,
+            Punctuation,
+            Number,
+            Punctuation,
+            Punctuation,
+            Comment
+        ]
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn error_serde() {
+    use erl_tokenize::Error;
+
+    let error = Tokenizer::new("foo(\"a\"\"b\")")
+        .find_map(|t| t.err())
+        .unwrap();
+    assert!(matches!(error, Error::AdjacentStringLiterals { .. }));
+    let json = serde_json::to_string(&error).unwrap();
+    assert!(json.starts_with(r#"{"AdjacentStringLiterals":{"position":"#));
+    let decoded: Error = serde_json::from_str(&json).unwrap();
+    assert!(matches!(decoded, Error::AdjacentStringLiterals { .. }));
+    assert_eq!(decoded.position(), error.position());
+    assert_eq!(decoded.to_string(), error.to_string());
+
+    let json = r#"{"UnknownKeyword":{"position":{"filepath":"foo.erl","offset":3,"line":1,"column":4},"keyword":"bar"}}"#;
+    let decoded: Error = serde_json::from_str(json).unwrap();
+    match decoded {
+        Error::UnknownKeyword {
+            ref position,
+            ref keyword,
+        } => {
+            assert_eq!(keyword, "bar");
+            assert_eq!(position.offset(), 3);
+            assert_eq!(
+                position.filepath().map(|p| p.to_str().unwrap()),
+                Some("foo.erl")
+            );
+        }
+        _ => panic!("{decoded:?}"),
+    }
+    assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+}
+
+#[test]
+fn tokenized_document() {
+    use erl_tokenize::TokenizedDocument;
+
+    fn make_document() -> TokenizedDocument {
+        let mut text = String::from("foo() ->\n");
+        text.push_str("    \"bar\".");
+        TokenizedDocument::new(text)
+    }
+
+    let doc = make_document();
+    assert_eq!(doc.iter().collect::<Result<Vec<_>, _>>().unwrap().len(), 12);
+
+    let token = doc.token_at(16).unwrap();
+    assert_eq!(token.text(), "\"bar\"");
+    assert_eq!(token.start_position().line(), 2);
+    assert_eq!(doc.token_at(13).unwrap().text(), "\"bar\"");
+    assert_eq!(doc.token_at(0).unwrap().text(), "foo");
+    assert_eq!(doc.token_at(8).unwrap().text(), "\n");
+    assert!(doc.token_at(doc.text().len()).is_none());
+
+    let doc = TokenizedDocument::from(String::from("foo \"bar"));
+    assert_eq!(doc.token_at(1).unwrap().text(), "foo");
+    assert!(doc.token_at(5).is_none());
+    assert_eq!(doc.into_text(), "foo \"bar");
+}
+
+#[test]
+fn escript_mode() {
+    let src = "#!/usr/bin/env escript\n%%! -smp enable\nmain(_) -> #{}.\n";
+    let tokens = Tokenizer::new(src)
+        .escript_mode(true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let shebang = tokens[0].as_comment_token().unwrap();
+    assert!(shebang.is_shebang());
+    assert_eq!(shebang.value(), "/usr/bin/env escript");
+    let emu_args = tokens[2].as_comment_token().unwrap();
+    assert!(!emu_args.is_shebang());
+    assert_eq!(emu_args.value(), "%! -smp enable");
+    assert_eq!(
+        tokens.iter().filter(|t| t.text() == "#").count(),
+        1,
+        "`#` in the body is not affected"
+    );
+
+    // Disabled by default.
+    let tokens = Tokenizer::tokenize(src).unwrap();
+    assert_eq!(tokens[0].text(), "#");
+    assert_eq!(tokens[1].text(), "!");
+}
+
+#[test]
+fn sigil_decoded_contents() {
+    fn decode(text: &str) -> (String, String) {
+        match Tokenizer::new(text).next() {
+            Some(Ok(Token::SigilString(t))) => (
+                t.raw_value().1.to_owned(),
+                t.decoded_content().unwrap().into_owned(),
+            ),
+            t => panic!("{t:?}"),
+        }
+    }
+
+    assert_eq!(
+        decode(r#"~"a\tb""#),
+        (r"a\tb".to_owned(), "a\tb".to_owned())
+    );
+    assert_eq!(
+        decode(r#"~s"a\"b""#),
+        (r#"a\"b"#.to_owned(), "a\"b".to_owned())
+    );
+    assert_eq!(decode(r"~b(a\)b)"), (r"a\)b".to_owned(), "a)b".to_owned()));
+    assert_eq!(
+        decode(r#"~B"a\tb""#),
+        (r"a\tb".to_owned(), r"a\tb".to_owned())
+    );
+    assert_eq!(
+        decode(r"~S[a\x41]"),
+        (r"a\x41".to_owned(), r"a\x41".to_owned())
+    );
+    assert_eq!(
+        decode("~\"\"\"\n  a\\tb\n  \"\"\""),
+        (r"  a\tb".to_owned(), r"a\tb".to_owned())
+    );
+
+    // `value()` processes escape sequences as a normal string does.
+    let tokens = Tokenizer::tokenize(r#"~"a\tb" ~B"a\tb" ~S[a\]b]"#).unwrap();
+    let values = tokens
+        .iter()
+        .filter_map(|t| match t {
+            Token::SigilString(t) => Some(t.value().1),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(values, ["a\tb", "a\tb", "a]b"]);
+
+    assert_eq!(tokens[0].to_erl_scan_term(), r#"{string,1,"a\tb"}"#);
+}
+
+#[test]
+fn whitespace_newline_counts() {
+    let count =
+        |text: &str| erl_tokenize::whitespace_newline_count(&Tokenizer::tokenize(text).unwrap());
+    assert_eq!(count("\n\n\n"), 3);
+    assert_eq!(count("foo.\r\n\t \nbar."), 2);
+    assert_eq!(count("foo. bar."), 0);
+    assert_eq!(count("\"a\nb\""), 0);
+
+    let tokens = Tokenizer::tokenize("\n \n").unwrap();
+    let per_token = tokens
+        .iter()
+        .map(|t| t.as_whitespace_token().unwrap().newline_count())
+        .collect::<Vec<_>>();
+    assert_eq!(per_token, [1, 0, 1]);
+}
+
+#[test]
+fn tokens_in_range() {
+    let srcs = [
+        r#"-module(foo).
+foo() ->
+    "a
+b". % comment.
+bar(X) -> X + 1.
+"#,
+        // A string and a quoted atom containing a dot at the end of a line.
+        r#"foo() -> "a.
+b. c", 'd.
+e'.
+bar() -> ok.
+"#,
+    ];
+    for src in srcs {
+        let all = Tokenizer::tokenize(src).unwrap();
+        let mut form_starts = vec![Position::new()];
+        let mut tokenizer = Tokenizer::new(src);
+        while tokenizer.next_form().is_some() {
+            form_starts.push(tokenizer.next_position());
+        }
+        for start in 0..src.len() {
+            for end in start..=src.len() {
+                let expected = all
+                    .iter()
+                    .filter(|t| {
+                        let (s, e) = (t.start_position().offset(), t.end_position().offset());
+                        if start == end {
+                            s <= start && start < e
+                        } else {
+                            s < end && start < e
+                        }
+                    })
+                    .map(|t| (t.text(), t.start_position()))
+                    .collect::<Vec<_>>();
+                let tokens = Tokenizer::tokens_in_range(src, start..end).unwrap();
+                let actual = tokens
+                    .iter()
+                    .map(|t| (t.text(), t.start_position()))
+                    .collect::<Vec<_>>();
+                assert_eq!(actual, expected, "range: {start}..{end}");
+
+                for form_start in form_starts.iter().filter(|p| p.offset() <= start) {
+                    let tokens =
+                        Tokenizer::tokens_in_range_from(src, form_start.clone(), start..end)
+                            .unwrap();
+                    let actual = tokens
+                        .iter()
+                        .map(|t| (t.text(), t.start_position()))
+                        .collect::<Vec<_>>();
+                    assert_eq!(
+                        actual, expected,
+                        "range: {start}..{end} from {form_start:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn render_tokens() {
+    let src = r#"%% Module comment
+-module(foo).
+-export([bar/1]).
+
+-doc """
+  Hello
+  """.
+bar(X) when is_atom(X) ->
+    <<$a, "b\tc", 'D'/utf8, ~s{e}, 1.5e-3, 16#FF>>;
+bar(_) -> #{k => v}#{k := w}.
+"#;
+    let tokens = Tokenizer::tokenize(src).unwrap();
+    assert_eq!(erl_tokenize::render(&tokens), src);
+}
+
+#[test]
+fn from_value_validations() {
+    use erl_tokenize::tokens::{AtomToken, CommentToken, FloatToken, VariableToken};
+
+    let pos = Position::new();
+    assert!(FloatToken::from_value(f64::NAN, pos.clone()).is_err());
+    assert!(FloatToken::from_value(f64::NEG_INFINITY, pos.clone()).is_err());
+    assert!(FloatToken::from_value(-1.5, pos.clone()).is_err());
+    assert!(FloatToken::from_value(-0.0, pos.clone()).is_err());
+    assert!(FloatToken::from_value(-f64::MIN_POSITIVE, pos.clone()).is_err());
+    assert!(AtomToken::from_value("foo\nbar", pos.clone()).is_err());
+    assert!(AtomToken::from_value("\0", pos.clone()).is_err());
+    assert!(CommentToken::from_value("foo\nbar", pos.clone()).is_err());
+    assert!(VariableToken::from_value("foo", pos.clone()).is_err());
+
+    // The resulting texts can be tokenized again.
+    for value in [0.0, 1.0, 1e20, 1.5e-7, 123.456, f64::MAX, f64::MIN_POSITIVE] {
+        let token = FloatToken::from_value(value, pos.clone()).unwrap();
+        let tokens = Tokenizer::tokenize(token.text()).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].as_float_token().map(|t| t.value()), Some(value));
+    }
+    let token = AtomToken::from_value("foo bar", pos.clone()).unwrap();
+    let tokens = Tokenizer::tokenize(token.text()).unwrap();
+    assert_eq!(
+        tokens[0].as_atom_token().map(|t| t.value()),
+        Some("foo bar")
+    );
+}
+
+#[test]
+fn long_fraction_float_precision() {
+    // Decimal floats are converted at once by the standard library, so that
+    // the result is the nearest `f64` even for long fractions.
+    let text = "0.1000000000000000055511151231257827021181583404541015625000001";
+    let tokens = Tokenizer::tokenize(text).unwrap();
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].as_float_token().map(|t| t.value()), Some(0.1));
+
+    let text = "3.141_592_653_589_793_238_462_643_383_279_502_884_197e0";
+    let tokens = Tokenizer::tokenize(text).unwrap();
+    assert_eq!(
+        tokens[0].as_float_token().map(|t| t.value()),
+        Some(std::f64::consts::PI)
+    );
+
+    // Based floats are not supported by this tokenizer.
+    let tokens = Tokenizer::tokenize("16#fefe.fefe#e16").unwrap();
+    assert!(tokens[0].as_integer_token().is_some());
+}
+
+#[test]
+fn integer_from_decimal_str() {
+    use erl_tokenize::tokens::IntegerToken;
+
+    let pos = Position::new();
+    for text in ["0", "007", "1_2_3", "123456789012345678901234567890"] {
+        let token = IntegerToken::from_decimal_str(text, pos.clone()).unwrap();
+        let expected = IntegerToken::from_text(text, pos.clone()).unwrap();
+        assert_eq!(token.value(), expected.value());
+        assert_eq!(token.text(), text);
+    }
+    for text in ["", "_", "_1", "1_", "1__2", "1a", "-1", "1.0", " 1", "2#10"] {
+        assert!(
+            IntegerToken::from_decimal_str(text, pos.clone()).is_err(),
+            "{text:?}"
+        );
+    }
+}
+
+#[test]
+fn operator_arities() {
+    use erl_tokenize::values::{Keyword, Symbol};
+
+    let src = "- + * / ++ -- ! = == =< ( ] , ; -> || <- <= <:- <:= => := bnot not div andalso case";
+    let arities = Tokenizer::new(src)
+        .map(|t| t.unwrap())
+        .filter_map(|t| match t {
+            Token::Symbol(t) => Some((t.text().to_owned(), t.value().arities())),
+            Token::Keyword(t) => Some((t.text().to_owned(), t.value().arities())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let expected: &[(&str, &[u8])] = &[
+        ("-", &[1, 2]),
+        ("+", &[1, 2]),
+        ("*", &[2]),
+        ("/", &[2]),
+        ("++", &[2]),
+        ("--", &[2]),
+        ("!", &[2]),
+        ("=", &[2]),
+        ("==", &[2]),
+        ("=<", &[2]),
+        ("(", &[]),
+        ("]", &[]),
+        (",", &[]),
+        (";", &[]),
+        ("->", &[]),
+        ("||", &[]),
+        ("<-", &[]),
+        ("<=", &[]),
+        ("<:-", &[]),
+        ("<:=", &[]),
+        ("=>", &[]),
+        (":=", &[]),
+        ("bnot", &[1]),
+        ("not", &[1]),
+        ("div", &[2]),
+        ("andalso", &[2]),
+        ("case", &[]),
+    ];
+    assert_eq!(
+        arities,
+        expected
+            .iter()
+            .map(|&(s, a)| (s.to_owned(), a))
+            .collect::<Vec<_>>()
+    );
+    assert!(Symbol::Hyphen.arities().contains(&1));
+    assert!(!Keyword::When.arities().contains(&1));
+}
+
+#[test]
+fn display_keywords_and_symbols() {
+    use erl_tokenize::values::{Keyword, Symbol};
+
+    assert_eq!(Keyword::Receive.to_string(), "receive");
+    assert_eq!(format!("{:>4}", Symbol::RightArrow), "  ->");
+    fn len<S: AsRef<str>>(s: S) -> usize {
+        s.as_ref().len()
+    }
+    assert_eq!(len(Keyword::Andalso), 7);
+    assert_eq!(len(Symbol::ExactNotEq), 3);
+}
+
+#[test]
+fn comments_terminated_by_newline() {
+    let comments = |src: &str| {
+        Tokenizer::new(src)
+            .map(|t| t.unwrap())
+            .filter_map(|t| t.as_comment_token().map(|t| t.terminated_by_newline()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(comments("% a\r\nfoo. % b\n% c"), [true, true, false]);
+    assert_eq!(comments("%\n"), [true]);
+    assert_eq!(comments("%"), [false]);
+
+    let tokens = Tokenizer::new("#!/usr/bin/env escript\n")
+        .escript_mode(true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert!(tokens[0]
+        .as_comment_token()
+        .is_some_and(|t| t.terminated_by_newline()));
+}
+
+#[test]
+fn adjacent_tokens() {
+    use erl_tokenize::are_adjacent;
+
+    let lexical = |src: &str| {
+        Tokenizer::new(src)
+            .map(|t| t.unwrap())
+            .filter(|t| t.is_lexical_token())
+            .collect::<Vec<_>>()
+    };
+
+    let tokens = lexical("foo(");
+    assert!(are_adjacent(&tokens[0], &tokens[1]));
+    assert!(!are_adjacent(&tokens[1], &tokens[0]));
+
+    let tokens = lexical("foo (");
+    assert!(!are_adjacent(&tokens[0], &tokens[1]));
+}
+
+#[test]
+fn tokenize_records_and_maps() {
+    use erl_tokenize::values::Symbol;
+
+    let symbols = |src: &str| {
+        Tokenizer::new(src)
+            .map(|t| t.unwrap())
+            .filter_map(|t| t.as_symbol_token().map(|t| t.value()))
+            .collect::<Vec<_>>()
+    };
+
+    let src = "X#rec.field";
+    assert_eq!(tokenize!(src), ["X", "#", "rec", ".", "field"]);
+    assert_eq!(symbols(src), [Symbol::Sharp, Symbol::Dot]);
+    let tokens = Tokenizer::tokenize(src).unwrap();
+    assert!(!tokens[3].is_terminator_dot(tokens.get(4)));
+
+    let src = "X#rec.field1.";
+    assert_eq!(tokenize!(src), ["X", "#", "rec", ".", "field1", "."]);
+
+    let src = "#{a => 1}";
+    assert_eq!(tokenize!(src), ["#", "{", "a", " ", "=>", " ", "1", "}"]);
+    assert_eq!(
+        symbols(src),
+        [
+            Symbol::Sharp,
+            Symbol::OpenBrace,
+            Symbol::DoubleRightArrow,
+            Symbol::CloseBrace
+        ]
+    );
+
+    let src = "M#{k := v}";
+    assert_eq!(
+        tokenize!(src),
+        ["M", "#", "{", "k", " ", ":=", " ", "v", "}"]
+    );
+    assert_eq!(
+        symbols(src),
+        [
+            Symbol::Sharp,
+            Symbol::OpenBrace,
+            Symbol::MapMatch,
+            Symbol::CloseBrace
+        ]
+    );
+}
+
+#[test]
+fn peek_kind() {
+    let src = r#"-module(foo). f(X) when X > 0 -> [$a, "b", 'c', 1, 2.5, 16#FF, ~s"d", case]. % e"#;
+    let mut tokenizer = Tokenizer::new(src);
+    while let Some(kind) = tokenizer.peek_kind() {
+        let token = tokenizer.next().unwrap().unwrap();
+        assert_eq!(kind.unwrap(), token.kind(), "{:?}", token.text());
+    }
+    assert!(tokenizer.next().is_none());
+
+    let tokenizer = Tokenizer::new("\"foo");
+    assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::String);
+
+    let tokenizer = Tokenizer::new("#!/usr/bin/env escript").escript_mode(true);
+    assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Comment);
+}
+
+#[test]
+fn unknown_keyword() {
+    use erl_tokenize::tokens::KeywordToken;
+    use erl_tokenize::Error;
+
+    let pos = Position::new() + 4;
+    for text in ["foo", "'case'", "casex"] {
+        match KeywordToken::from_text(text, pos.clone()) {
+            Err(Error::UnknownKeyword { position, keyword }) => {
+                assert_eq!(position.offset(), 4);
+                assert_eq!(keyword, text);
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+    assert!(matches!(
+        KeywordToken::from_text("Case", pos),
+        Err(Error::InvalidAtomToken { .. })
+    ));
+
+    // The tokenizer never reports unknown keywords.
+    assert!(Tokenizer::new("foo casex 'case'").all(|t| t.unwrap().kind() != TokenKind::Keyword));
+}
+
+#[test]
+fn check_brackets() {
+    use erl_tokenize::values::Symbol;
+    use erl_tokenize::Error;
+
+    fn check(text: &str) -> Result<usize, (usize, Symbol)> {
+        let mut count = 0;
+        for token in Tokenizer::new(text).check_brackets() {
+            match token {
+                Ok(_) => count += 1,
+                Err(Error::UnbalancedBracket { position, found }) => {
+                    return Err((position.offset(), found))
+                }
+                Err(e) => panic!("{}", e),
+            }
+        }
+        Ok(count)
+    }
+
+    assert_eq!(check("f({[a], <<1>>})."), Ok(14));
+    assert_eq!(check("[)"), Err((1, Symbol::CloseParen)));
+    assert_eq!(check("({)}"), Err((2, Symbol::CloseParen)));
+    assert_eq!(check("a)"), Err((1, Symbol::CloseParen)));
+    assert_eq!(check("f(a, [b"), Err((5, Symbol::OpenSquare)));
+
+    // The iteration stops after the error.
+    assert_eq!(Tokenizer::new("[)]").check_brackets().count(), 2);
+    assert_eq!(Tokenizer::new("(\"foo").check_brackets().count(), 2);
+}
+
+#[test]
+fn byte_len_and_char_len() {
+    use erl_tokenize::tokens::{AtomToken, StringToken};
+
+    let pos = Position::new();
+    let token = Token::from_text("comté", pos.clone()).unwrap();
+    assert_eq!((token.byte_len(), token.char_len()), (6, 5));
+    assert_eq!(token.end_position().offset(), token.byte_len());
+
+    let atom = AtomToken::from_text("'comté'", pos.clone()).unwrap();
+    assert_eq!((atom.byte_len(), atom.char_len()), (8, 7));
+
+    let string = StringToken::from_text(r#""comté""#, pos.clone()).unwrap();
+    assert_eq!((string.byte_len(), string.char_len()), (8, 7));
+
+    let token = Token::from_text("foo", pos).unwrap();
+    assert_eq!((token.byte_len(), token.char_len()), (3, 3));
+}
+
+#[test]
+fn char_backslash_at_eof() {
+    use erl_tokenize::tokens::CharToken;
+    use erl_tokenize::Error;
+
+    let pos = Position::new();
+    for text in [r"$\", r"$\x4", r"$\^"] {
+        match CharToken::from_text(text, pos.clone()) {
+            Err(e @ Error::InvalidEscapedChar { .. }) => assert_eq!(e.position().offset(), 1),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    let e = Tokenizer::new(r"foo $\").nth(2).unwrap().unwrap_err();
+    assert!(matches!(e, Error::InvalidEscapedChar { .. }));
+    assert_eq!(e.position().offset(), 5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn shared_filepath() {
+    use std::sync::Arc;
+
+    let mut tokenizer = Tokenizer::new("foo(\n  \"bar\").\n% baz\n");
+    tokenizer.set_filepath("a.erl");
+    let tokens = tokenizer.collect::<Result<Vec<_>, _>>().unwrap();
+    let path = tokens[0].start_position().filepath_arc().unwrap().clone();
+    for token in &tokens {
+        for pos in [token.start_position(), token.end_position()] {
+            assert!(Arc::ptr_eq(pos.filepath_arc().unwrap(), &path));
+        }
+    }
+
+    let mut other = Tokenizer::new("foo");
+    other.set_filepath("a.erl");
+    let token = other.next().unwrap().unwrap();
+    let other_path = token.start_position().filepath_arc().unwrap().clone();
+    assert!(!Arc::ptr_eq(&other_path, &path));
+    assert_eq!(other_path, path);
+
+    assert!(Position::new().filepath_arc().is_none());
+}
+
+#[test]
+fn custom_comment_prefix() {
+    let tokens = Tokenizer::new("# foo\nbar. % baz")
+        .with_comment_prefix('#')
+        .collect::<Result<Vec<_>, _>>();
+    assert!(tokens.is_err()); // `%` is no longer a comment
+
+    let tokens = Tokenizer::new("# foo\nbar. # baz")
+        .with_comment_prefix('#')
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        tokens.iter().map(|t| t.kind()).collect::<Vec<_>>(),
+        [
+            TokenKind::Comment,
+            TokenKind::Whitespace,
+            TokenKind::Atom,
+            TokenKind::Symbol,
+            TokenKind::Whitespace,
+            TokenKind::Comment
+        ]
+    );
+    assert_eq!(tokens[0].text(), "# foo");
+    assert_eq!(tokens[5].as_comment_token().unwrap().value(), " baz");
+
+    let tokenizer = Tokenizer::new("# foo").with_comment_prefix('#');
+    assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Comment);
+
+    // Only the first line of an escript is a shebang.
+    let tokens = Tokenizer::new("#! hi")
+        .with_comment_prefix('#')
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let comment = tokens[0].as_comment_token().unwrap();
+    assert!(!comment.is_shebang());
+    assert_eq!(comment.value(), "! hi");
+
+    // The default behavior is unchanged.
+    assert_eq!(tokenize!("#{} % foo"), ["#", "{", "}", " ", "% foo"]);
+}
+
+#[test]
+fn eof_token() {
+    let src = "foo. % bar";
+    let tokens = Tokenizer::new(src)
+        .with_eof_token(true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let eofs = tokens
+        .iter()
+        .filter(|t| t.kind() == TokenKind::Eof)
+        .collect::<Vec<_>>();
+    assert_eq!(eofs.len(), 1);
+    assert_eq!(tokens.last().unwrap().kind(), TokenKind::Eof);
+    assert_eq!(eofs[0].start_position().offset(), src.len());
+    assert_eq!(eofs[0].end_position().offset(), src.len());
+    assert_eq!(eofs[0].text(), "");
+    assert!(!eofs[0].is_lexical_token() && !eofs[0].is_hidden_token());
+
+    let mut tokenizer = Tokenizer::new("").with_eof_token(true);
+    assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Eof);
+    assert!(tokenizer.next().unwrap().unwrap().as_eof_token().is_some());
+    assert!(tokenizer.peek_kind().is_none());
+    assert!(tokenizer.next().is_none());
+
+    let tokens = Tokenizer::new("foo.")
+        .with_eof_token(true)
+        .collect::<Vec<_>>();
+    assert!(tokens[1]
+        .as_ref()
+        .unwrap()
+        .is_terminator_dot(tokens[2].as_ref().ok()));
+
+    // Disabled by default.
+    assert_eq!(Tokenizer::new("foo.").count(), 2);
+}
+
+#[test]
+fn integer_digit_separators() {
+    use erl_tokenize::tokens::IntegerToken;
+
+    let pos = Position::new();
+    let integer = |text| IntegerToken::from_text(text, pos.clone()).unwrap();
+
+    assert!(integer("1_000").has_separators());
+    assert_eq!(integer("1_000").grouping(), [1, 3]);
+
+    assert!(integer("1_0_0").has_separators());
+    assert_eq!(integer("1_0_0").grouping(), [1, 1, 1]);
+
+    assert!(!integer("1000").has_separators());
+    assert_eq!(integer("1000").grouping(), [4]);
+
+    assert!(!integer("1_6#FF").has_separators());
+    assert_eq!(integer("2#1010_1010").grouping(), [4, 4]);
+
+    let tokens = Tokenizer::new("-1_000")
+        .fold_signs()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let token = tokens[0].as_integer_token().unwrap();
+    assert_eq!(token.grouping(), [1, 3]);
+}
+
+#[test]
+fn whitespace_text() {
+    use erl_tokenize::tokens::WhitespaceToken;
+    use erl_tokenize::values::Whitespace;
+
+    let pos = Position::new();
+    let token = WhitespaceToken::from_text("\u{a0}", pos.clone()).unwrap();
+    assert_eq!(token.value(), Whitespace::NoBreakSpace);
+    assert_eq!(token.text(), "\u{a0}");
+    assert_eq!(token.end_position().offset(), 2);
+
+    for ws in [
+        Whitespace::Space,
+        Whitespace::Tab,
+        Whitespace::Return,
+        Whitespace::Newline,
+        Whitespace::VerticalTab,
+        Whitespace::FormFeed,
+        Whitespace::NoBreakSpace,
+    ] {
+        assert_eq!(ws.as_str(), ws.as_char().to_string());
+        let token = WhitespaceToken::from_text(ws.as_str(), pos.clone()).unwrap();
+        assert_eq!(token.value(), ws);
+        assert_eq!(token.text(), ws.as_str());
+    }
+}
+
+#[test]
+fn integer_as_char() {
+    use erl_tokenize::tokens::IntegerToken;
+
+    let pos = Position::new();
+    let as_char = |text| {
+        IntegerToken::from_text(text, pos.clone())
+            .unwrap()
+            .as_char()
+    };
+
+    assert_eq!(as_char("65"), Some('A'));
+    assert_eq!(as_char("16#1F600"), Some('😀'));
+    assert_eq!(as_char("16#D800"), None);
+    assert_eq!(as_char("16#110000"), None);
+    assert_eq!(as_char("99999999999999999999"), None);
+
+    let tokens = Tokenizer::new("-65")
+        .fold_signs()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens[0].as_integer_token().unwrap().as_char(), None);
+}
+
+#[test]
+fn from_bytes() {
+    use erl_tokenize::Error;
+
+    let tokens = Tokenizer::from_bytes("foo() -> \"é\".".as_bytes())
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens.len(), 8);
+
+    let mut bytes = b"-module(foo).\nbar() ->\n    \"ab".to_vec();
+    bytes.push(0xC3); // truncated sequence
+    bytes.extend_from_slice(b"\".\n");
+    match Tokenizer::from_bytes(&bytes) {
+        Err(Error::InvalidUtf8 { position }) => {
+            assert_eq!(position.offset(), 30);
+            assert_eq!(position.line(), 3);
+            assert_eq!(position.column(), 8);
+        }
+        other => panic!("{:?}", other.map(|t| t.text().to_owned())),
+    }
+}
+
+#[test]
+fn tokenize_dots() {
+    use erl_tokenize::values::Symbol;
+
+    fn symbols(text: &str) -> Vec<Symbol> {
+        Tokenizer::new(text)
+            .map(|t| t.unwrap().as_symbol_token().unwrap().value())
+            .collect()
+    }
+
+    assert_eq!(symbols("..."), [Symbol::TripleDot]);
+    assert_eq!(symbols(".."), [Symbol::DoubleDot]);
+    assert_eq!(symbols("."), [Symbol::Dot]);
+    assert_eq!(symbols("...."), [Symbol::TripleDot, Symbol::Dot]);
+    assert_eq!(symbols("....."), [Symbol::TripleDot, Symbol::DoubleDot]);
+}
+
+#[test]
+fn is_valid() {
+    for text in [
+        "",
+        "foo.",
+        "f(X) -> X + 1. % comment",
+        "<<\"a\"/utf8>>",
+        "$\\n",
+    ] {
+        assert!(erl_tokenize::is_valid(text), "{:?}", text);
+    }
+    for text in ["\"foo", "'bar", "$", "16#", "\u{1}", "foo \"bar"] {
+        assert!(!erl_tokenize::is_valid(text), "{:?}", text);
+    }
+}
+
+#[test]
+fn integer_digits() {
+    use erl_tokenize::tokens::IntegerToken;
+
+    let pos = Position::new();
+    let digits = |text| {
+        IntegerToken::from_text(text, pos.clone())
+            .unwrap()
+            .digits()
+            .to_owned()
+    };
+
+    assert_eq!(digits("16#aB0e"), "aB0e");
+    assert_eq!(digits("16#FF"), "FF");
+    assert_eq!(digits("16#ff"), "ff");
+    assert_eq!(digits("10"), "10");
+    assert_eq!(digits("1_000"), "1_000");
+    assert_eq!(digits("1_6#a_b"), "a_b");
+    assert_eq!(digits("2#1010 "), "1010");
+
+    let tokens = Tokenizer::new("-16#FF")
+        .fold_signs()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens[0].as_integer_token().unwrap().digits(), "FF");
+}
+
+#[test]
+fn with_trivia() {
+    let items = Tokenizer::new("foo % c\n bar.")
+        .with_trivia()
+        .map(|item| {
+            let (trivia, token) = item.unwrap();
+            let trivia = trivia
+                .iter()
+                .map(|t| t.text().to_owned())
+                .collect::<Vec<_>>();
+            (trivia, token.text().to_owned())
+        })
+        .collect::<Vec<_>>();
+    let expected: Vec<(Vec<&str>, &str)> = vec![
+        (vec![], "foo"),
+        (vec![" ", "% c", "\n", " "], "bar"),
+        (vec![], "."),
+        (vec![], ""),
+    ];
+    assert_eq!(items.len(), expected.len());
+    for ((trivia, token), (expected_trivia, expected_token)) in items.iter().zip(&expected) {
+        assert_eq!(trivia, expected_trivia);
+        assert_eq!(token, expected_token);
+    }
+
+    // Trailing trivia
+    let items = Tokenizer::new("foo. % end\n")
+        .with_eof_token(true)
+        .with_trivia()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[2].0.len(), 3);
+    assert_eq!(items[2].1.kind(), TokenKind::Eof);
+    assert_eq!(items[2].1.start_position().offset(), 11);
+
+    // Errors
+    let mut iter = Tokenizer::new("foo \"bar").with_trivia();
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn empty_and_escaped_quoted_atoms() {
+    use erl_tokenize::tokens::AtomToken;
+
+    let pos = Position::new();
+
+    let atom = AtomToken::from_text("''", pos.clone()).unwrap();
+    assert_eq!(atom.value(), "");
+    assert_eq!(atom.text(), "''");
+
+    let atom = AtomToken::from_text("'' foo", pos.clone()).unwrap();
+    assert_eq!(atom.text(), "''");
+
+    let atom = AtomToken::from_text(r"'\''", pos.clone()).unwrap();
+    assert_eq!(atom.value(), "'");
+    assert_eq!(atom.text(), r"'\''");
+
+    assert_eq!(
+        tokenize!("f('', '\\'')"),
+        ["f", "(", "''", ",", " ", r"'\''", ")"]
+    );
+}
+
+#[test]
+fn sigil_kind() {
+    use erl_tokenize::tokens::SigilStringToken;
+    use erl_tokenize::values::SigilKind;
+
+    let pos = Position::new();
+    let tokens = [
+        r#"~b"x""#,
+        r#"~"x""#,
+        r#"~s(x)"#,
+        r#"~B"x""#,
+        r#"~S[x]"#,
+        r#"~foo"x""#,
+    ]
+    .map(|text| SigilStringToken::from_text(text, pos.clone()).unwrap());
+    let kinds = tokens.iter().map(|t| t.sigil_kind()).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            SigilKind::Binary,
+            SigilKind::Default,
+            SigilKind::String,
+            SigilKind::Verbatim,
+            SigilKind::Verbatim,
+            SigilKind::Custom("foo"),
+        ]
+    );
+}
+
+#[test]
+fn positions_of() {
+    let src = "-module(foo).\n-export([bar/0]).\nbar() -> 'baz'.\n";
+    let ranges = erl_tokenize::positions_of(src, TokenKind::Atom).unwrap();
+    let atoms = ranges
+        .iter()
+        .map(|r| &src[r.start.offset()..r.end.offset()])
+        .collect::<Vec<_>>();
+    assert_eq!(atoms, ["module", "foo", "export", "bar", "bar", "'baz'"]);
+    assert_eq!(ranges[3].start.line(), 2);
+    assert_eq!(ranges[4].start.line(), 3);
+
+    assert!(erl_tokenize::positions_of("foo \"bar", TokenKind::Atom).is_err());
+    assert!(erl_tokenize::positions_of("foo", TokenKind::String)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn maybe_feature() {
+    fn kinds(text: &str, enabled: bool) -> Vec<TokenKind> {
+        Tokenizer::new(text)
+            .enable_maybe_feature(enabled)
+            .map(|t| t.unwrap().kind())
+            .filter(|k| *k != TokenKind::Whitespace)
+            .collect()
+    }
+
+    let src = "maybe ok ?= X else Y -> Y end";
+    assert_eq!(
+        kinds(src, true),
+        [
+            TokenKind::Keyword,
+            TokenKind::Atom,
+            TokenKind::Symbol,
+            TokenKind::Variable,
+            TokenKind::Keyword,
+            TokenKind::Variable,
+            TokenKind::Symbol,
+            TokenKind::Variable,
+            TokenKind::Keyword
+        ]
+    );
+    assert_eq!(
+        kinds(src, false),
+        [
+            TokenKind::Atom,
+            TokenKind::Atom,
+            TokenKind::Symbol,
+            TokenKind::Variable,
+            TokenKind::Atom,
+            TokenKind::Variable,
+            TokenKind::Symbol,
+            TokenKind::Variable,
+            TokenKind::Keyword
+        ]
+    );
+
+    let tokenizer = Tokenizer::new("else").enable_maybe_feature(false);
+    assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Atom);
+    let tokenizer = Tokenizer::new("else");
+    assert_eq!(tokenizer.peek_kind().unwrap().unwrap(), TokenKind::Keyword);
+}
+
+#[test]
+fn line_text() {
+    use erl_tokenize::line_text;
+
+    let src = "-module(foo).\r\nbar() ->\n    ok.";
+    let tokens = Tokenizer::tokenize(src).unwrap();
+    let lines = tokens
+        .iter()
+        .map(|t| line_text(src, &t.start_position()).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(lines[0], "-module(foo).");
+    assert_eq!(lines[6], "-module(foo)."); // "\r"
+    assert_eq!(lines[7], "-module(foo)."); // "\n"
+    assert_eq!(lines[8], "bar() ->");
+    assert_eq!(lines.last().unwrap(), &"    ok.");
+
+    let end = tokens.last().unwrap().end_position();
+    assert_eq!(line_text(src, &end), Some("    ok."));
+    assert_eq!(line_text(src, &(end + 1)), None);
+    assert_eq!(line_text("", &Position::new()), Some(""));
+}
+
+#[test]
+fn float_missing_digit_positions() {
+    use erl_tokenize::tokens::FloatToken;
+    use erl_tokenize::Error;
+
+    fn error_offset(text: &str) -> usize {
+        match FloatToken::from_text(text, Position::new()) {
+            Err(e @ Error::ExpectedDigit { .. }) => e.position().offset(),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    assert_eq!(error_offset(".5"), 0);
+    assert_eq!(error_offset("1."), 2);
+    assert_eq!(error_offset("1.e3"), 2);
+    assert_eq!(error_offset("1.5e"), 4);
+    assert_eq!(error_offset("1.5e+ "), 5);
+    assert_eq!(error_offset("12_.3"), 3);
+
+    let e = FloatToken::from_text("1.", Position::new() + 10).unwrap_err();
+    assert_eq!(e.position().offset(), 12);
+}
+
+#[test]
+fn normalize_whitespace() {
+    let src = "foo() ->\n\n    bar(1,\t 2). % comment  \n";
+    let tokens = Tokenizer::new(src)
+        .normalize_whitespace()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let normalized = erl_tokenize::render(&tokens);
+    assert_eq!(normalized, "foo() ->\n\nbar(1, 2). % comment  \n");
+
+    // Positions reflect the normalized output.
+    let retokenized = Tokenizer::tokenize(&normalized).unwrap();
+    assert_eq!(tokens.len(), retokenized.len());
+    for (a, b) in tokens.iter().zip(&retokenized) {
+        assert_eq!(a.text(), b.text());
+        assert_eq!(a.start_position(), b.start_position());
+        assert_eq!(a.end_position(), b.end_position());
+    }
+
+    let mut iter = Tokenizer::new("foo  \"bar").normalize_whitespace();
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn ascii_whitespace_only() {
+    use erl_tokenize::Error;
+
+    let tokens = Tokenizer::new("a\u{a0}b")
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens.len(), 3);
+    assert_eq!(tokens[1].kind(), TokenKind::Whitespace);
+
+    let mut tokenizer = Tokenizer::new("a\u{a0}b").ascii_whitespace_only(true);
+    assert_eq!(tokenizer.next().unwrap().unwrap().text(), "a");
+    assert!(tokenizer.peek_kind().unwrap().is_err());
+    match tokenizer.next() {
+        Some(Err(Error::InvalidWhitespaceToken { position })) => assert_eq!(position.offset(), 1),
+        other => panic!("{:?}", other),
+    }
+
+    let tokens = Tokenizer::new("a \t\r\nb")
+        .ascii_whitespace_only(true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens.len(), 6);
+}
+
+#[test]
+fn attributes() {
+    let src = r#"%% Header
+-module(foo).
+-export([bar/1]).
+
+bar(X) -> X - 1.
+
+-spec baz() -> ok. % comment
+baz() -> ok."#;
+    let attrs = Tokenizer::new(src)
+        .attributes()
+        .map(|a| {
+            let (name, args) = a.unwrap();
+            let args = args.iter().map(|t| t.text().to_owned()).collect::<Vec<_>>();
+            (name.value().to_owned(), args.concat())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        attrs,
+        [
+            ("module".to_owned(), "(foo)".to_owned()),
+            ("export".to_owned(), "([bar/1])".to_owned()),
+            ("spec".to_owned(), "baz()->ok".to_owned()),
+        ]
+    );
+
+    let src = "-if(?OTP_RELEASE >= 27).\n-define(A, 1).\n-else.\n-define(A, 2).\n-endif.";
+    let attrs = Tokenizer::new(src)
+        .attributes()
+        .map(|a| a.unwrap().0.value().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(attrs, ["if", "define", "else", "define", "endif"]);
+
+    let mut iter = Tokenizer::new("-module(foo).\n-bar(\"baz").attributes();
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn max_token_bytes() {
+    use erl_tokenize::Error;
+
+    fn too_long_at(text: &str, limit: usize) -> Option<usize> {
+        for token in Tokenizer::new(text).max_token_bytes(limit) {
+            match token {
+                Ok(_) => {}
+                Err(Error::TokenTooLong { position, limit: l }) => {
+                    assert_eq!(l, limit);
+                    return Some(position.offset());
+                }
+                Err(e) => panic!("{}", e),
+            }
+        }
+        None
+    }
+
+    let long_atom = "a".repeat(100);
+    assert_eq!(too_long_at(&format!("foo({})", long_atom), 16), Some(4));
+    assert_eq!(too_long_at(&format!("foo({})", long_atom), 100), None);
+    assert_eq!(too_long_at(&format!("foo({})", long_atom), 99), Some(4));
+
+    assert_eq!(too_long_at(&format!("{}é", "c".repeat(16)), 16), Some(0));
+    assert_eq!(too_long_at("foo(\"bar\", 123456).", 8), None);
+
+    // Tokens are never split at the limit.
+    assert_eq!(too_long_at("123.5", 3), Some(0));
+    assert_eq!(too_long_at("1.5", 1), Some(0));
+    assert_eq!(too_long_at("=:=", 1), Some(0));
+    assert_eq!(too_long_at("X =:= 1.5", 3), None);
+
+    assert_eq!(too_long_at("X = 1.0e+5", 6), None);
+
+    // Oversized unterminated tokens are reported without scanning to the end.
+    let unterminated = format!("x = \"{}", "b".repeat(1000));
+    assert_eq!(too_long_at(&unterminated, 16), Some(4));
+    let unterminated = format!("x = '{}", "b".repeat(1000));
+    assert_eq!(too_long_at(&unterminated, 16), Some(4));
+
+    // Genuine errors are still reported.
+    let e = Tokenizer::new("\"foo")
+        .max_token_bytes(16)
+        .next()
+        .unwrap()
+        .unwrap_err();
+    assert!(matches!(e, Error::NoClosingQuotation { .. }));
+}
+
+#[test]
+fn whitespace_sequence() {
+    use erl_tokenize::values::Whitespace;
+
+    let tokens = Tokenizer::tokenize("\t \n").unwrap();
+    assert_eq!(
+        erl_tokenize::whitespace_sequence(&tokens),
+        [Whitespace::Tab, Whitespace::Space, Whitespace::Newline]
+    );
+
+    let tokens = Tokenizer::tokenize("foo(\r\n  %x\n)").unwrap();
+    assert_eq!(
+        erl_tokenize::whitespace_sequence(&tokens),
+        [
+            Whitespace::Return,
+            Whitespace::Newline,
+            Whitespace::Space,
+            Whitespace::Space,
+            Whitespace::Newline
+        ]
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn display_unix_path() {
+    use std::path::PathBuf;
+
+    let mut tokenizer = Tokenizer::new("foo\nbar");
+    tokenizer.set_filepath(PathBuf::from("a\\b.erl"));
+    let tokens = tokenizer.collect::<Result<Vec<_>, _>>().unwrap();
+    let pos = tokens[2].start_position();
+    assert_eq!(pos.display_unix_path(), "a/b.erl:2:1");
+    assert_eq!(pos.to_string(), "a\\b.erl:2:1");
+
+    assert_eq!(Position::new().display_unix_path(), "<unknown>:1:1");
+}
+
+#[test]
+fn pretty_print_works() {
+    use erl_tokenize::{pretty_print, PrettyOptions};
+
+    let pretty = |src: &str, options: PrettyOptions| {
+        pretty_print(&Tokenizer::tokenize(src).unwrap(), options)
+    };
+    assert_eq!(pretty("[1,2,3]", PrettyOptions::new()), "[1, 2, 3]");
+    assert_eq!(
+        pretty("X=-1*(Y -2)", PrettyOptions::new()),
+        "X = -1 * (Y - 2)"
+    );
+    assert_eq!(
+        pretty("lists:map(fun foo/1,L)", PrettyOptions::new()),
+        "lists:map(fun foo/1, L)"
+    );
+    assert_eq!(pretty("#foo{a=1}", PrettyOptions::new()), "#foo{a = 1}");
+    assert_eq!(pretty("X#foo.a", PrettyOptions::new()), "X#foo.a");
+    assert_eq!(
+        pretty("<<X:8/binary>>", PrettyOptions::new()),
+        "<<X:8/binary>>"
+    );
+    assert_eq!(
+        pretty("%% doc\nfoo()->ok.\n\n\n\nbar()->ok.", PrettyOptions::new()),
+        "%% doc\nfoo() -> ok.\n\nbar() -> ok.\n"
+    );
+    assert_eq!(
+        pretty(
+            "foo()->ok.\n\n\nbar()->ok.",
+            PrettyOptions::new().collapse_blank_lines(false)
+        ),
+        "foo() -> ok.\n\n\nbar() -> ok.\n"
+    );
+    assert_eq!(
+        pretty("foo() ->\n[1,\n2].", PrettyOptions::new()),
+        "foo() ->\n    [1,\n        2].\n"
+    );
+}
+
+#[test]
+fn sort_tokens_by_position() {
+    let tokens = Tokenizer::tokenize("foo(X, 1.5) -> [X | \"bar\"].").unwrap();
+    let mut shuffled = tokens.clone();
+    let len = shuffled.len();
+    for i in 0..len {
+        shuffled.swap(i, (i * 7 + 3) % len);
+    }
+    assert_ne!(
+        shuffled.iter().map(|t| t.text()).collect::<Vec<_>>(),
+        tokens.iter().map(|t| t.text()).collect::<Vec<_>>()
+    );
+
+    shuffled.sort_by(Token::cmp_by_position);
+    assert_eq!(
+        shuffled
+            .iter()
+            .map(|t| t.start_offset())
+            .collect::<Vec<_>>(),
+        tokens.iter().map(|t| t.start_offset()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        shuffled.iter().map(|t| t.text()).collect::<Vec<_>>(),
+        tokens.iter().map(|t| t.text()).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn integer_from_value_with_radix() {
+    use erl_tokenize::tokens::IntegerToken;
+
+    for (radix, text) in [(2, "2#11111111"), (16, "16#ff"), (36, "36#73")] {
+        let token =
+            IntegerToken::from_value_with_radix(255u32.into(), radix, Position::new()).unwrap();
+        assert_eq!(token.text(), text);
+
+        let parsed = IntegerToken::from_text(token.text(), Position::new()).unwrap();
+        assert_eq!(parsed.value(), token.value());
+        assert_eq!(parsed.text(), text);
+    }
+
+    for radix in [0, 1, 37] {
+        assert!(matches!(
+            IntegerToken::from_value_with_radix(255u32.into(), radix, Position::new()),
+            Err(erl_tokenize::Error::InvalidRadix { radix: r, .. }) if r == radix
+        ));
+    }
+}
+
+#[test]
+fn form_byte_ranges_works() {
+    use erl_tokenize::form_byte_ranges;
+
+    let text = r#"-module(foo).
+-export([bar/1, 'baz.qux'/0]).
+
+%% A comment. With dots.
+bar(X) ->
+    R = #rec{a = X},
+    R#rec.a.
+
+'baz.qux'() -> "not. a. terminator".
+"#;
+    let ranges = form_byte_ranges(text).unwrap();
+    assert_eq!(ranges.first().map(|r| r.start), Some(0));
+    assert_eq!(ranges.last().map(|r| r.end), Some(text.len()));
+    assert!(ranges.windows(2).all(|w| w[0].end == w[1].start));
+
+    let forms = ranges
+        .iter()
+        .map(|r| text[r.clone()].trim())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        forms,
+        [
+            "-module(foo).",
+            "-export([bar/1, 'baz.qux'/0]).",
+            "%% A comment. With dots.\nbar(X) ->\n    R = #rec{a = X},\n    R#rec.a.",
+            "'baz.qux'() -> \"not. a. terminator\".",
+            ""
+        ]
+    );
+
+    // Each range can be tokenized independently.
+    let texts = ranges
+        .iter()
+        .flat_map(|r| Tokenizer::new(&text[r.clone()]).map(|t| t.unwrap().text().to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(texts, tokenize!(text));
+
+    assert!(form_byte_ranges("foo() -> \"bar.").is_err());
+}
+
+#[test]
+fn collect_into_token_text() {
+    use erl_tokenize::TokenText;
+
+    let src = "foo(X) -> % comment\n    [X, $a, \"bar\", 1.5].\n";
+    let text = Tokenizer::new(src)
+        .map(|t| t.unwrap())
+        .collect::<TokenText>();
+    assert_eq!(text.as_str(), src);
+    assert_eq!(text.to_string(), src);
+
+    let mut text = TokenText::new();
+    text.extend(Tokenizer::tokenize("foo").unwrap());
+    text.extend(Tokenizer::tokenize("(1)").unwrap());
+    assert_eq!(String::from(text), "foo(1)");
+}
+
+#[test]
+fn unexpected_char() {
+    use erl_tokenize::tokens::SymbolToken;
+    use erl_tokenize::Error;
+
+    let error = Tokenizer::new("foo(`bar`)").find_map(|t| t.err()).unwrap();
+    match error {
+        Error::UnexpectedChar { ch, ref position } => {
+            assert_eq!(ch, '`');
+            assert_eq!(position.offset(), 4);
+        }
+        ref e => panic!("{e:?}"),
+    }
+    assert_eq!(
+        error.to_string(),
+        "unexpected character '`' (<unknown>:1:5)"
+    );
+
+    // Backticks are allowed as sigil delimiters.
+    assert!(Tokenizer::tokenize("~`bar`").is_ok());
+
+    for c in ['`', '\\', '\u{2028}', '€'] {
+        let text = c.to_string();
+        assert!(matches!(
+            SymbolToken::from_text(&text, Position::new()),
+            Err(Error::UnexpectedChar { ch, .. }) if ch == c
+        ));
+    }
+    assert!(matches!(
+        SymbolToken::from_text("", Position::new()),
+        Err(Error::InvalidSymbolToken { .. })
+    ));
+}
+
+#[test]
+fn gaps() {
+    let gaps = |src: &str| {
+        Tokenizer::new(src)
+            .gaps()
+            .map(|t| t.map(|(t, n)| (t.text().to_owned(), n)))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    assert_eq!(
+        gaps("foo  bar").unwrap(),
+        [("foo".to_owned(), 0), ("bar".to_owned(), 2)]
+    );
+    assert_eq!(
+        gaps("  [1,\n\t2] % c\n .").unwrap(),
+        [
+            ("[".to_owned(), 2),
+            ("1".to_owned(), 0),
+            (",".to_owned(), 0),
+            ("2".to_owned(), 2),
+            ("]".to_owned(), 0),
+            (".".to_owned(), 2),
+        ]
+    );
+    assert_eq!(gaps("%% only a comment\n").unwrap(), []);
+
+    let mut iter = Tokenizer::new("a \"b").gaps();
+    assert_eq!(iter.next().unwrap().unwrap().1, 0);
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn char_hex_escape_without_braces() {
+    use erl_tokenize::tokens::CharToken;
+    use erl_tokenize::Error;
+
+    let pos = Position::new();
+    let token = CharToken::from_text(r"$\x41g", pos.clone()).unwrap();
+    assert_eq!(token.value(), 'A');
+    assert_eq!(token.text(), r"$\x41");
+    assert_eq!(tokenize!(r"$\x41g"), [r"$\x41", "g"]);
+    assert_eq!(
+        tokenize!(r"[$\x41,$\x{42}g]"),
+        ["[", r"$\x41", ",", r"$\x{42}", "g", "]"]
+    );
+
+    for text in [r"$\xG", r"$\x4", r"$\x4g"] {
+        assert!(matches!(
+            CharToken::from_text(text, pos.clone()),
+            Err(Error::InvalidEscapedChar { .. })
+        ));
+    }
+}
+
+#[test]
+fn enumerate_lexical() {
+    let items = Tokenizer::new("-module(foo).\n\n%% comment\nbar() ->  ok.")
+        .with_eof_token(true)
+        .enumerate_lexical()
+        .map(|(i, t)| (i, t.unwrap().text().to_owned()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        items.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+        (0..items.len()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        items.iter().map(|(_, t)| t.as_str()).collect::<Vec<_>>(),
+        ["-", "module", "(", "foo", ")", ".", "bar", "(", ")", "->", "ok", "."]
+    );
+
+    let mut iter = Tokenizer::new("foo bar \"baz").enumerate_lexical();
+    assert_eq!(iter.next().map(|(i, t)| (i, t.is_ok())), Some((0, true)));
+    assert_eq!(iter.next().map(|(i, t)| (i, t.is_ok())), Some((1, true)));
+    assert_eq!(iter.next().map(|(i, t)| (i, t.is_ok())), Some((2, false)));
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn parse_number() {
+    use erl_tokenize::parse::parse_number;
+    use erl_tokenize::Error;
+
+    let pos = Position::new();
+    let token = parse_number("1.0", pos.clone()).unwrap();
+    assert_eq!(token.as_float_token().map(|t| t.value()), Some(1.0));
+
+    let token = parse_number("1", pos.clone()).unwrap();
+    assert_eq!(
+        token.as_integer_token().map(|t| t.value().clone()),
+        Some(1u32.into())
+    );
+
+    let token = parse_number("16#ff", pos.clone()).unwrap();
+    assert_eq!(
+        token.as_integer_token().map(|t| t.value().clone()),
+        Some(255u32.into())
+    );
+
+    let token = parse_number("1_000.5e-1", pos.clone()).unwrap();
+    assert_eq!(token.as_float_token().map(|t| t.value()), Some(100.05));
+
+    // Based floats are not supported, so `.1` is regarded as trailing characters.
+    match parse_number("2#0.1", pos.clone()) {
+        Err(Error::InvalidIntegerToken { position }) => assert_eq!(position.offset(), 3),
+        other => panic!("{other:?}"),
+    }
+    match parse_number("1.0.", pos.clone()) {
+        Err(Error::InvalidFloatToken { position }) => assert_eq!(position.offset(), 3),
+        other => panic!("{other:?}"),
+    }
+    for text in ["", "1.", "foo", "-1", "1 "] {
+        assert!(parse_number(text, pos.clone()).is_err(), "{text:?}");
+    }
+}
+
+#[test]
+fn speculative_lookahead_with_cursor() {
+    let mut tokenizer = Tokenizer::new(String::from("foo(X) -> X.\nbar."));
+
+    // Looks ahead for `(` after skipping the first token.
+    let mut cursor = tokenizer.cursor();
+    cursor.next();
+    let is_call = matches!(cursor.peek(), Some(Ok(Token::Symbol(_))));
+    assert!(is_call);
+    assert_eq!(tokenizer.next_position().offset(), 0);
+
+    // Rolls back by dropping the cursor.
+    assert_eq!(tokenizer.next().unwrap().unwrap().text(), "foo");
+
+    // Commits the speculatively consumed tokens.
+    let mut cursor = tokenizer.cursor();
+    let consumed = cursor
+        .by_ref()
+        .map(|t| t.unwrap())
+        .take_while(|t| t.text() != "->")
+        .map(|t| t.text().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(consumed, ["(", "X", ")", " "]);
+    let position = cursor.position();
+    tokenizer.set_position(position);
+    assert_eq!(
+        tokenizer
+            .map(|t| t.unwrap().text().to_owned())
+            .collect::<Vec<_>>(),
+        [" ", "X", ".", "\n", "bar", "."]
+    );
+}
+
+#[test]
+fn integer_followed_by_dot() {
+    let pos = Position::new();
+
+    let token = Token::from_text("123.foo", pos.clone()).unwrap();
+    assert_eq!(token.kind(), TokenKind::Integer);
+    assert_eq!(token.text(), "123");
+    assert_eq!(tokenize!("123.foo"), ["123", ".", "foo"]);
+
+    let token = Token::from_text("123.", pos.clone()).unwrap();
+    assert_eq!(token.kind(), TokenKind::Integer);
+    assert_eq!(token.text(), "123");
+    let tokens = Tokenizer::tokenize("123.").unwrap();
+    assert_eq!(tokens.len(), 2);
+    assert!(tokens[1].is_terminator_dot(None));
+    assert_eq!(
+        tokenize!("X = 123.\n"),
+        ["X", " ", "=", " ", "123", ".", "\n"]
+    );
+
+    let token = Token::from_text("123.456", pos.clone()).unwrap();
+    assert_eq!(token.kind(), TokenKind::Float);
+    assert_eq!(token.text(), "123.456");
+    assert_eq!(tokenize!("123.456."), ["123.456", "."]);
+}
+
+#[test]
+fn doc_attributes() {
+    let src = r#"-module(foo).
+-moduledoc """
+  A module.
+  """.
+
+%% Comments between the name and the value are skipped.
+-doc %% comment
+  """
+  Hello
+  """.
+-spec bar() -> ok.
+bar() -> ok.
+
+-doc("Baz").
+baz() -> ok.
+
+-doc false.
+qux() -> ok.
+
+-doc ~"Sigil".
+quux() -> ok.
+"#;
+    let docs = Tokenizer::new(src)
+        .doc_attributes()
+        .map(|t| t.map(|(name, doc)| (name.value().to_owned(), doc.value().to_owned())))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        docs,
+        [
+            ("moduledoc".to_owned(), "A module.".to_owned()),
+            ("doc".to_owned(), "Hello".to_owned()),
+            ("doc".to_owned(), "Baz".to_owned()),
+        ]
+    );
+
+    let mut iter = Tokenizer::new("-doc \"foo\".\n-doc \"bar").doc_attributes();
+    assert!(iter.next().unwrap().is_ok());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn token_count() {
+    use erl_tokenize::{token_count, Error};
+
+    for src in [
+        "",
+        "foo",
+        "-module(foo).\n\n%% comment\nbar(X) -> [X, $a, \"baz\", 1.5, 16#ff].\n",
+    ] {
+        let tokens = Tokenizer::new(src).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(token_count(src).unwrap(), tokens.len());
+    }
+
+    let error = token_count("foo bar \"baz").unwrap_err();
+    assert!(matches!(error, Error::NoClosingQuotation { .. }));
+    assert_eq!(error.position().offset(), 8);
+}
+
+#[test]
+fn maximal_munch_symbols() {
+    use erl_tokenize::values::Symbol;
+
+    let symbols = |src: &str| {
+        Tokenizer::new(src)
+            .map(|t| t.unwrap().as_symbol_token().unwrap().value())
+            .collect::<Vec<_>>()
+    };
+
+    // Like `erl_scan`, the longest symbol at the current position is always taken.
+    assert_eq!(symbols("+++"), [Symbol::PlusPlus, Symbol::Plus]);
+    assert_eq!(symbols("---"), [Symbol::MinusMinus, Symbol::Hyphen]);
+    assert_eq!(symbols("--->"), [Symbol::MinusMinus, Symbol::RightArrow]);
+    assert_eq!(symbols("-->"), [Symbol::MinusMinus, Symbol::Greater]);
+    assert_eq!(symbols("++++"), [Symbol::PlusPlus, Symbol::PlusPlus]);
+    assert_eq!(symbols("=:=="), [Symbol::ExactEq, Symbol::Match]);
+}
+
+#[test]
+fn position_advance() {
+    let src = "foo(\n  \"bar\nbaz\") % qux\n.";
+    let tokens = Tokenizer::tokenize(src).unwrap();
+    for token in &tokens {
+        let end = token.start_position().advance(token.text());
+        assert_eq!(end, token.end_position());
+    }
+    assert_eq!(
+        Position::new().advance(src),
+        tokens.last().unwrap().end_position()
+    );
+    assert_eq!(Position::new().advance(""), Position::new());
+}
+
+#[test]
+fn string_raw_value() {
+    use erl_tokenize::tokens::StringToken;
+
+    let pos = Position::new();
+    for (text, value, raw_value) in [
+        (r#""b\tar""#, "b\tar", r"b\tar"),
+        (r#""foo""#, "foo", "foo"),
+        (r#""""#, "", ""),
+        (r#""a\"b\\""#, "a\"b\\", r#"a\"b\\"#),
+        ("\"\"\"\n  a\\tb\n  \"\"\"", "a\\tb", "  a\\tb"),
+    ] {
+        let token = StringToken::from_text(text, pos.clone()).unwrap();
+        assert_eq!(token.value(), value, "{text:?}");
+        assert_eq!(token.raw_value(), raw_value, "{text:?}");
+    }
+}
+
+#[test]
+fn forbid_multiline_strings() {
+    use erl_tokenize::Error;
+
+    let src = "foo() ->\n    \"bar\nbaz\".";
+    assert!(tokenize!(src).contains(&"\"bar\nbaz\"".to_owned()));
+
+    let error = Tokenizer::new(src)
+        .forbid_multiline_strings(true)
+        .find_map(|t| t.err())
+        .unwrap();
+    assert!(matches!(error, Error::MultilineString { .. }));
+    assert_eq!(error.position().offset(), 13);
+    assert_eq!(error.position().line(), 2);
+
+    for src in [
+        r#""bar\nbaz""#,
+        "\"\"\"\n  bar\n  baz\n  \"\"\"",
+        "'bar\nbaz'",
+    ] {
+        let tokens = Tokenizer::new(src)
+            .forbid_multiline_strings(true)
+            .collect::<Result<Vec<_>, _>>();
+        assert!(tokens.is_ok(), "{src:?}");
+    }
+}
+
+#[test]
+fn token_kind_indices() {
+    assert_eq!(TokenKind::all().len(), TokenKind::count());
+    for (i, kind) in TokenKind::all().iter().enumerate() {
+        assert_eq!(kind.index(), i);
+    }
+
+    let tokens = Tokenizer::new("foo(\"bar\", $a, 1.5) -> ok. % baz")
+        .with_eof_token(true)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let mut seen = [false; TokenKind::count()];
+    for token in &tokens {
+        assert_eq!(TokenKind::all()[token.kind().index()], token.kind());
+        seen[token.kind().index()] = true;
+    }
+    assert!(seen[TokenKind::Eof.index()]);
+    assert!(!seen[TokenKind::Variable.index()]);
+}
+
+#[test]
+fn visit_tokens() {
+    use erl_tokenize::tokens::{AtomToken, CommentToken};
+    use erl_tokenize::{visit_tokens, TokenVisitor};
+
+    #[derive(Default)]
+    struct Counter {
+        atoms: usize,
+        comments: usize,
+    }
+    impl TokenVisitor for Counter {
+        fn on_atom(&mut self, _token: &AtomToken) {
+            self.atoms += 1;
+        }
+
+        fn on_comment(&mut self, _token: &CommentToken) {
+            self.comments += 1;
+        }
+    }
+
+    let src = "-module(foo).\n%% comment\nbar() -> {ok, 'baz qux', case}.";
+    let mut counter = Counter::default();
+    visit_tokens(src, &mut counter).unwrap();
+    assert_eq!(counter.atoms, 5);
+    assert_eq!(counter.comments, 1);
+
+    // Overriding `on_token` sees every token.
+    struct All(usize);
+    impl TokenVisitor for All {
+        fn on_token(&mut self, _token: &Token) {
+            self.0 += 1;
+        }
+    }
+    let mut all = All(0);
+    visit_tokens(src, &mut all).unwrap();
+    assert_eq!(all.0, Tokenizer::tokenize(src).unwrap().len());
+
+    let mut counter = Counter::default();
+    assert!(visit_tokens("foo bar \"baz", &mut counter).is_err());
+    assert_eq!(counter.atoms, 2);
+}
+
+#[test]
+fn kind_for_leading_char() {
+    use erl_tokenize::kind_for_leading_char;
+
+    for (c, kind) in [
+        ('$', Some(TokenKind::Char)),
+        ('"', Some(TokenKind::String)),
+        ('~', Some(TokenKind::SigilString)),
+        ('0', Some(TokenKind::Integer)),
+        ('9', Some(TokenKind::Integer)),
+        ('%', Some(TokenKind::Comment)),
+        ('\'', Some(TokenKind::Atom)),
+        ('c', Some(TokenKind::Atom)),
+        ('X', Some(TokenKind::Variable)),
+        ('_', Some(TokenKind::Variable)),
+        (' ', Some(TokenKind::Whitespace)),
+        ('\n', Some(TokenKind::Whitespace)),
+        ('(', Some(TokenKind::Symbol)),
+        ('=', Some(TokenKind::Symbol)),
+        ('.', Some(TokenKind::Symbol)),
+        ('`', None),
+        ('\u{1}', None),
+        ('\u{C}', Some(TokenKind::Whitespace)),
+    ] {
+        assert_eq!(kind_for_leading_char(c), kind, "{c:?}");
+    }
+
+    // Consistent with the kinds of the tokens starting with the characters.
+    let src = "foo(X, $a, \"b\", 1, 'c', ~\"d\") -> ok. % e";
+    for token in Tokenizer::tokenize(src).unwrap() {
+        let c = token.text().chars().next().unwrap();
+        let expected = match token.kind() {
+            TokenKind::Keyword => TokenKind::Atom,
+            TokenKind::Float => TokenKind::Integer,
+            kind => kind,
+        };
+        assert_eq!(kind_for_leading_char(c), Some(expected));
+    }
+}
+
+#[test]
+fn unicode_variables_and_atoms() {
+    for (src, kind, text) in [
+        ("Äpfel", TokenKind::Variable, "Äpfel"),
+        ("ÄX = 1", TokenKind::Variable, "ÄX"),
+        ("Xä", TokenKind::Variable, "Xä"),
+        ("Σ", TokenKind::Variable, "Σ"),
+        ("äpfel", TokenKind::Atom, "äpfel"),
+        ("σ", TokenKind::Atom, "σ"),
+        ("élan_Vital@1", TokenKind::Atom, "élan_Vital@1"),
+    ] {
+        let token = Token::from_text(src, Position::new()).unwrap();
+        assert_eq!((token.kind(), token.text()), (kind, text), "{src:?}");
+
+        let tokens = Tokenizer::tokenize(src).unwrap();
+        assert_eq!(tokens[0].kind(), kind, "{src:?}");
+        assert_eq!(tokens[0].text(), text, "{src:?}");
+        assert_eq!(
+            erl_tokenize::kind_for_leading_char(src.chars().next().unwrap()),
+            Some(kind)
+        );
+    }
+}
+
+#[test]
+fn with_base_position() {
+    use erl_tokenize::Edit;
+
+    // A code block starting at line 10, column 5 (offset 200) of a host document.
+    let src = "foo() ->\n    \"bar\".";
+    let tokens = Tokenizer::new(src)
+        .with_base_position(10, 5, 200)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens.len(), Tokenizer::tokenize(src).unwrap().len());
+    let locations = tokens
+        .iter()
+        .map(|t| {
+            let pos = t.start_position();
+            (t.text(), pos.line(), pos.column(), pos.offset())
+        })
+        .filter(|(text, ..)| !text.trim().is_empty())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        locations,
+        [
+            ("foo", 10, 5, 200),
+            ("(", 10, 8, 203),
+            (")", 10, 9, 204),
+            ("->", 10, 11, 206),
+            ("\"bar\"", 11, 5, 213),
+            (".", 11, 10, 218),
+        ]
+    );
+    assert_eq!(
+        tokens.last().unwrap().end_position().offset(),
+        200 + src.len()
+    );
+
+    let error = Tokenizer::new("foo\n  \"bar")
+        .with_base_position(10, 1, 0)
+        .find_map(|t| t.err())
+        .unwrap();
+    assert_eq!(error.position().line(), 11);
+    assert_eq!(error.position().column(), 3);
+
+    let mut tokenizer = Tokenizer::new("foo bar").with_base_position(10, 1, 50);
+    tokenizer.next();
+    let position = tokenizer.next_position();
+    assert_eq!(position.offset(), 53);
+    assert_eq!(tokenizer.next().unwrap().unwrap().text(), " ");
+    tokenizer.set_position(position);
+    assert_eq!(
+        tokenizer
+            .map(|t| t.unwrap().text().to_owned())
+            .collect::<Vec<_>>(),
+        [" ", "bar"]
+    );
+
+    // Edits are specified by offsets in the text.
+    let old_tokens = Tokenizer::new("foo() -> 1.\nbar() -> 2.\n")
+        .with_base_position(10, 1, 100)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let mut tokenizer =
+        Tokenizer::new("foo() -> 1.\nbar() -> 20.\n").with_base_position(10, 1, 100);
+    let tokens = tokenizer
+        .retokenize(&Edit::new(21..22, "20"), &old_tokens)
+        .unwrap();
+    let expected = Tokenizer::new("foo() -> 1.\nbar() -> 20.\n")
+        .with_base_position(10, 1, 100)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        tokens
+            .iter()
+            .map(|t| (t.text(), t.start_position()))
+            .collect::<Vec<_>>(),
+        expected
+            .iter()
+            .map(|t| (t.text(), t.start_position()))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn atom_names() {
+    use erl_tokenize::{atom_names, unique_atom_names};
+
+    let names = |atoms: Vec<(String, Position)>| {
+        atoms
+            .into_iter()
+            .map(|(name, pos)| (name, pos.offset()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(atom_names("foo:bar(baz)").unwrap()),
+        [
+            ("foo".to_owned(), 0),
+            ("bar".to_owned(), 4),
+            ("baz".to_owned(), 8)
+        ]
+    );
+
+    let src = "foo() -> case 'foo' of bar -> 'b\\x61r'; _ -> end_of_file end.";
+    assert_eq!(
+        names(atom_names(src).unwrap()),
+        [
+            ("foo".to_owned(), 0),
+            ("foo".to_owned(), 14),
+            ("bar".to_owned(), 23),
+            ("bar".to_owned(), 30),
+            ("end_of_file".to_owned(), 45)
+        ]
+    );
+    assert_eq!(
+        names(unique_atom_names(src).unwrap()),
+        [
+            ("foo".to_owned(), 0),
+            ("bar".to_owned(), 23),
+            ("end_of_file".to_owned(), 45)
+        ]
+    );
+
+    assert!(atom_names("foo 'bar").is_err());
+    assert!(unique_atom_names("foo 'bar").is_err());
+}
+
+#[test]
+fn symbol_byte_len() {
+    use erl_tokenize::tokens::SymbolToken;
+    use erl_tokenize::values::Symbol;
+
+    let mut tokenizer = Tokenizer::new("foo ");
+    tokenizer.next();
+    let start = tokenizer.next_position();
+    for &symbol in Symbol::all() {
+        let token = SymbolToken::from_value(symbol, start.clone());
+        assert_eq!(token.byte_len(), symbol.as_str().len());
+        assert_eq!(
+            token.end_position().offset() - token.start_position().offset(),
+            symbol.as_str().len(),
+            "{symbol:?}"
+        );
+        assert_eq!(
+            token.end_position().column() - token.start_position().column(),
+            symbol.as_str().len(),
+            "{symbol:?}"
+        );
+
+        let token = SymbolToken::from_text(symbol.as_str(), start.clone()).unwrap();
+        assert_eq!(token.value(), symbol);
+        assert_eq!(token.byte_len(), symbol.as_str().len());
+    }
+
+    let all = Symbol::all();
+    for (i, a) in all.iter().enumerate() {
+        assert!(all[i + 1..].iter().all(|b| a != b));
+    }
+}
+
+#[test]
+fn next_form_with_eof_token() {
+    let mut tokenizer = Tokenizer::new("foo.\nbar.").with_eof_token(true);
+    let mut forms = Vec::new();
+    while let Some(form) = tokenizer.next_form() {
+        let form = form.unwrap();
+        forms.push(form.iter().map(|t| t.text().to_owned()).collect::<Vec<_>>());
+        assert!(forms.len() <= 3);
+    }
+    assert_eq!(forms, [vec!["foo", "."], vec!["\n", "bar", "."], vec![""]]);
+    assert!(tokenizer.next().is_none());
+}
@@ -0,0 +1,17 @@
+//! Checks that the core tokenization APIs are available without the `std` feature.
+//!
+//! Run with `cargo test --no-default-features --test no_std`.
+#![cfg(not(feature = "std"))]
+use erl_tokenize::{Lexer, Position, PositionRange, Tokenizer};
+
+#[test]
+fn tokenize_without_std() {
+    let src = "foo(\"bar\") -> [$a, 1.5, 16#FF].";
+    let tokens = Tokenizer::new(src).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(erl_tokenize::render(&tokens), src);
+    assert_eq!(Lexer::new(src).count(), 13);
+
+    let error = Tokenizer::new("\"foo").next().unwrap().unwrap_err();
+    assert_eq!(error.position().to_string(), "<unknown>:1:1");
+    assert_eq!(tokens[1].start_position(), Position::new() + 3);
+}